@@ -0,0 +1,92 @@
+/// The 16 standard terminal colours, in palette order, with xterm's default values.
+const SYSTEM_COLORS: [(&str, [u8; 3]); 16] = [
+    ("black", [0, 0, 0]),
+    ("red", [205, 0, 0]),
+    ("green", [0, 205, 0]),
+    ("yellow", [205, 205, 0]),
+    ("blue", [0, 0, 238]),
+    ("magenta", [205, 0, 205]),
+    ("cyan", [0, 205, 205]),
+    ("white", [229, 229, 229]),
+    ("brightblack", [127, 127, 127]),
+    ("brightred", [255, 0, 0]),
+    ("brightgreen", [0, 255, 0]),
+    ("brightyellow", [255, 255, 0]),
+    ("brightblue", [92, 92, 255]),
+    ("brightmagenta", [255, 0, 255]),
+    ("brightcyan", [0, 255, 255]),
+    ("brightwhite", [255, 255, 255]),
+];
+
+/// The channel values used by the 6x6x6 colour cube at indexes 16-231.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The xterm 256 colour palette entry at `index`.
+pub(crate) fn palette(index: u8) -> (f32, f32, f32) {
+    let [red, green, blue] = match index {
+        0..=15 => SYSTEM_COLORS[index as usize].1,
+        16..=231 => {
+            let i = index as usize - 16;
+            [
+                CUBE_LEVELS[i / 36],
+                CUBE_LEVELS[i / 6 % 6],
+                CUBE_LEVELS[i % 6],
+            ]
+        }
+        // A greyscale ramp
+        232..=255 => [8 + (index - 232) * 10; 3],
+    };
+
+    (red as f32, green as f32, blue as f32)
+}
+
+/// Finds the palette index of one of the 16 standard colours by name, ignoring case.
+pub(crate) fn index_of(name: &str) -> Option<u8> {
+    let name = name.to_ascii_lowercase();
+
+    SYSTEM_COLORS
+        .iter()
+        .position(|(candidate, _)| *candidate == name)
+        .map(|i| i as u8)
+}
+
+/// Finds the palette entry closest to the given colour.
+pub(crate) fn nearest(red: f32, green: f32, blue: f32) -> u8 {
+    (0..=255)
+        .min_by(|a, b| {
+            let distance = |index| {
+                let (r, g, b) = palette(index);
+                (r - red).powi(2) + (g - green).powi(2) + (b - blue).powi(2)
+            };
+            distance(*a).total_cmp(&distance(*b))
+        })
+        .expect("the palette isn't empty")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn palette_entries() {
+        assert_eq!(palette(1), (205.0, 0.0, 0.0));
+        assert_eq!(palette(16), (0.0, 0.0, 0.0));
+        assert_eq!(palette(208), (255.0, 135.0, 0.0));
+        assert_eq!(palette(231), (255.0, 255.0, 255.0));
+        assert_eq!(palette(232), (8.0, 8.0, 8.0));
+        assert_eq!(palette(255), (238.0, 238.0, 238.0));
+    }
+
+    #[test]
+    fn names_and_nearest() {
+        assert_eq!(index_of("BrightMagenta"), Some(13));
+        assert_eq!(index_of("orange"), None);
+
+        // Exact matches round-trip, preferring the lowest index
+        for index in 0..=255 {
+            let (red, green, blue) = palette(index);
+            assert_eq!(palette(nearest(red, green, blue)), palette(index));
+        }
+        assert_eq!(nearest(250.0, 130.0, 10.0), 208);
+    }
+}
@@ -5,22 +5,270 @@ pub enum ColorType {
     Hsl,
     Rgb,
     Hex,
+    Name,
+    Hwb,
+    Hsv,
+    Lab,
+    Lch,
+    Oklab,
+    Oklch,
+    Cmyk,
+    Ycbcr,
+    Office,
+    Vba,
+    Latex,
+    LatexHtml,
+    Typst,
+    Troff,
+    Ansi,
+    /// The CSS `currentcolor` keyword, which stands in for another colour. It can
+    /// be parsed but isn't a notation colours can be converted to.
+    CurrentColor,
+    Color(ColorSpace),
+}
+
+/// Every name accepted for a colour type, other than the `color()` spaces.
+const COLOR_TYPE_NAMES: &[(&str, ColorType)] = &[
+    ("hsl", ColorType::Hsl),
+    ("hsla", ColorType::Hsl),
+    ("rgb", ColorType::Rgb),
+    ("rgba", ColorType::Rgb),
+    ("hex", ColorType::Hex),
+    ("name", ColorType::Name),
+    ("hwb", ColorType::Hwb),
+    ("hsv", ColorType::Hsv),
+    ("hsb", ColorType::Hsv),
+    ("lab", ColorType::Lab),
+    ("lch", ColorType::Lch),
+    ("oklab", ColorType::Oklab),
+    ("oklch", ColorType::Oklch),
+    ("cmyk", ColorType::Cmyk),
+    ("device-cmyk", ColorType::Cmyk),
+    ("ycbcr", ColorType::Ycbcr),
+    ("yuv", ColorType::Ycbcr),
+    ("office", ColorType::Office),
+    ("word", ColorType::Office),
+    ("vba", ColorType::Vba),
+    ("latex", ColorType::Latex),
+    ("latex-html", ColorType::LatexHtml),
+    ("typst", ColorType::Typst),
+    ("troff", ColorType::Troff),
+    ("groff", ColorType::Troff),
+    ("ansi", ColorType::Ansi),
+];
+
+/// Lists the accepted colour type names for error messages, e.g. `` `hsl`, `hsla` ``.
+pub(crate) fn color_type_names() -> String {
+    COLOR_TYPE_NAMES
+        .iter()
+        .map(|(name, _)| format!("`{}`", name))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl FromStr for ColorType {
     type Err = crate::Error;
 
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowercase = s.to_lowercase();
+
+        if let Some((_, color_type)) = COLOR_TYPE_NAMES.iter().find(|(name, _)| *name == lowercase)
+        {
+            return Ok(*color_type);
+        }
+
+        lowercase.parse().map(Self::Color).map_err(|_| {
+            let names = COLOR_TYPE_NAMES
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .chain(ColorSpace::ALL.iter().map(ColorSpace::to_string));
+
+            crate::Error::InvalidColorType {
+                name: s.to_string(),
+                suggestion: crate::suggest::closest(&lowercase, names),
+            }
+        })
+    }
+}
+
+impl Display for ColorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Hsl => "hsl",
+            Self::Rgb => "rgb",
+            Self::Hex => "hex",
+            Self::Name => "name",
+            Self::Hwb => "hwb",
+            Self::Hsv => "hsv",
+            Self::Lab => "lab",
+            Self::Lch => "lch",
+            Self::Oklab => "oklab",
+            Self::Oklch => "oklch",
+            Self::Cmyk => "cmyk",
+            Self::Ycbcr => "ycbcr",
+            Self::Office => "office",
+            Self::Vba => "vba",
+            Self::Latex => "latex",
+            Self::LatexHtml => "latex-html",
+            Self::Typst => "typst",
+            Self::Troff => "troff",
+            Self::Ansi => "ansi",
+            Self::CurrentColor => "currentcolor",
+            Self::Color(space) => return write!(f, "{}", space),
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The predefined colour spaces usable in the CSS `color()` function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    Srgb,
+    SrgbLinear,
+    DisplayP3,
+    A98Rgb,
+    ProphotoRgb,
+    Rec2020,
+    XyzD50,
+    XyzD65,
+}
+
+impl FromStr for ColorSpace {
+    type Err = crate::Error;
+
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_ref() {
-            "hsl" | "hsla" => Ok(Self::Hsl),
-            "rgb" | "rgba" => Ok(Self::Rgb),
-            "hex" => Ok(Self::Hex),
-            _ => Err(crate::Error::InvalidColorType(s.to_string())),
+            "srgb" => Ok(Self::Srgb),
+            "srgb-linear" => Ok(Self::SrgbLinear),
+            "display-p3" => Ok(Self::DisplayP3),
+            "a98-rgb" => Ok(Self::A98Rgb),
+            "prophoto-rgb" => Ok(Self::ProphotoRgb),
+            "rec2020" => Ok(Self::Rec2020),
+            "xyz-d50" => Ok(Self::XyzD50),
+            "xyz" | "xyz-d65" => Ok(Self::XyzD65),
+            _ => Err(crate::Error::UnknownColorSpace(s.to_string())),
+        }
+    }
+}
+
+impl Display for ColorSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Srgb => "srgb",
+            Self::SrgbLinear => "srgb-linear",
+            Self::DisplayP3 => "display-p3",
+            Self::A98Rgb => "a98-rgb",
+            Self::ProphotoRgb => "prophoto-rgb",
+            Self::Rec2020 => "rec2020",
+            Self::XyzD50 => "xyz-d50",
+            Self::XyzD65 => "xyz-d65",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl ColorSpace {
+    pub const ALL: [ColorSpace; 8] = [
+        Self::Srgb,
+        Self::SrgbLinear,
+        Self::DisplayP3,
+        Self::A98Rgb,
+        Self::ProphotoRgb,
+        Self::Rec2020,
+        Self::XyzD50,
+        Self::XyzD65,
+    ];
+
+    /// Whether components in this space describe a colour that sRGB can show.
+    pub fn in_srgb_gamut(self, components: [f32; 3]) -> bool {
+        in_srgb_gamut(self.to_linear_srgb(components))
+    }
+
+    /// Converts components in this space to linear-light sRGB, where 0-1 is the sRGB gamut.
+    fn to_linear_srgb(self, components: [f32; 3]) -> [f32; 3] {
+        let xyz = match self {
+            Self::Srgb => return components.map(srgb_to_linear),
+            Self::SrgbLinear => return components,
+            Self::DisplayP3 => multiply(&LINEAR_P3_TO_XYZ_D65, components.map(srgb_to_linear)),
+            Self::A98Rgb => multiply(&LINEAR_A98_TO_XYZ_D65, components.map(a98_to_linear)),
+            Self::ProphotoRgb => multiply(
+                &D50_TO_D65,
+                multiply(
+                    &LINEAR_PROPHOTO_TO_XYZ_D50,
+                    components.map(prophoto_to_linear),
+                ),
+            ),
+            Self::Rec2020 => multiply(
+                &LINEAR_REC2020_TO_XYZ_D65,
+                components.map(rec2020_to_linear),
+            ),
+            Self::XyzD50 => multiply(&D50_TO_D65, components),
+            Self::XyzD65 => components,
+        };
+
+        multiply(&XYZ_D65_TO_LINEAR_SRGB, xyz)
+    }
+
+    /// Converts linear-light sRGB to components in this space.
+    fn convert_from_linear_srgb(self, linear: [f32; 3]) -> [f32; 3] {
+        let xyz = multiply(&LINEAR_SRGB_TO_XYZ_D65, linear);
+
+        match self {
+            Self::Srgb => linear.map(linear_to_srgb),
+            Self::SrgbLinear => linear,
+            Self::DisplayP3 => multiply(&XYZ_D65_TO_LINEAR_P3, xyz).map(linear_to_srgb),
+            Self::A98Rgb => multiply(&XYZ_D65_TO_LINEAR_A98, xyz).map(linear_to_a98),
+            Self::ProphotoRgb => multiply(&XYZ_D50_TO_LINEAR_PROPHOTO, multiply(&D65_TO_D50, xyz))
+                .map(linear_to_prophoto),
+            Self::Rec2020 => multiply(&XYZ_D65_TO_LINEAR_REC2020, xyz).map(linear_to_rec2020),
+            Self::XyzD50 => multiply(&D65_TO_D50, xyz),
+            Self::XyzD65 => xyz,
+        }
+    }
+}
+
+/// A standard illuminant, whose white point XYZ values are relative to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Illuminant {
+    /// Horizon light, used for print and by ICC profiles and CSS Lab
+    D50,
+    /// Noon daylight, used by sRGB and most displays
+    D65,
+}
+
+impl Illuminant {
+    /// The `color()` space for XYZ relative to this illuminant.
+    fn xyz_space(self) -> ColorSpace {
+        match self {
+            Self::D50 => ColorSpace::XyzD50,
+            Self::D65 => ColorSpace::XyzD65,
+        }
+    }
+
+    /// The XYZ coordinates of the illuminant's white, with Y as 1.
+    fn white(self) -> [f32; 3] {
+        match self {
+            Self::D50 => D50_WHITE,
+            Self::D65 => D65_WHITE,
         }
     }
 }
 
-// TODO: Rename this to Color and delete Color, HslColor and RgbColor
+/// How colours outside of sRGB are brought into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamutMapping {
+    /// Clamp each channel separately. Cheap, but can shift the hue noticeably.
+    Clip,
+    /// Reduce chroma in OKLCh, keeping lightness and hue, as CSS Color 4 does.
+    ReduceChroma,
+}
+
+/// A colour in sRGB, remembering the notation it was created from.
+///
+/// Components are kept unrounded from 0 to 1, so converting between notations doesn't
+/// build up rounding error. The accessors and strings round for display. Colours given
+/// in a `color()` space, Lab or OKLab also keep their original components, which may be
+/// outside of sRGB, and are only clamped where sRGB is needed. See `gamut_map`.
 #[derive(Clone, Debug)]
 pub struct Color {
     parsed_as: ColorType,
@@ -28,15 +276,26 @@ pub struct Color {
     green: f32,
     blue: f32,
     alpha: f32,
+    /// Components that were given as `none`, in the order they appear in the
+    /// notation the colour was parsed as, followed by alpha.
+    missing: [bool; 5],
+    /// The space and unclamped components the colour was created from, for colours
+    /// that may be outside of sRGB. Lab and OKLab keep theirs as XYZ and linear sRGB.
+    space_components: Option<(ColorSpace, [f32; 3])>,
 }
 
 impl FromStr for Color {
     type Err = crate::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, color) = crate::parse::parse_color(s).map_err(|_| crate::Error::InvalidColor)?;
-
-        Ok(color)
+        match crate::parse::parse_color(s) {
+            Ok((_, color)) => Ok(color),
+            // A lone word can only have been meant as a colour name
+            Err(_) if !s.is_empty() && s.chars().all(|c| c.is_ascii_alphabetic()) => {
+                Self::from_name(s)
+            }
+            Err(err) => Err(crate::parse::invalid_color(s, err)),
+        }
     }
 }
 
@@ -49,12 +308,36 @@ impl PartialEq for Color {
     }
 }
 
+/// How far `Color::beautify` may move a colour, in CIEDE2000 units.
+pub const BEAUTIFY_MAX_DELTA_E: f32 = 3.0;
+
+/// The colour name used by named definitions (LaTeX, troff) when none is given.
+pub const DEFAULT_COLOR_NAME: &str = "mycolor";
+
 impl Display for Color {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self.parsed_as {
             ColorType::Hsl => self.hsl_string(),
             ColorType::Rgb => self.rgb_string(),
             ColorType::Hex => self.hex_string(),
+            ColorType::Name => self.name_string(),
+            ColorType::Hwb => self.hwb_string(),
+            ColorType::Hsv => self.hsv_string(),
+            ColorType::Lab => self.lab_string(),
+            ColorType::Lch => self.lch_string(),
+            ColorType::Oklab => self.oklab_string(),
+            ColorType::Oklch => self.oklch_string(),
+            ColorType::Cmyk => self.cmyk_string(),
+            ColorType::Ycbcr => self.ycbcr_string(),
+            ColorType::Office => self.office_string(),
+            ColorType::Vba => self.vba_string(),
+            ColorType::Latex => self.latex_string(DEFAULT_COLOR_NAME),
+            ColorType::LatexHtml => self.latex_html_string(DEFAULT_COLOR_NAME),
+            ColorType::Typst => self.typst_string(),
+            ColorType::Troff => self.troff_string(DEFAULT_COLOR_NAME),
+            ColorType::Ansi => self.ansi_string(),
+            ColorType::CurrentColor => "currentcolor".to_string(),
+            ColorType::Color(space) => self.color_string(space),
         };
         write!(f, "{}", s)
     }
@@ -62,32 +345,418 @@ impl Display for Color {
 
 impl Color {
     pub fn from_hsl(hue: f32, saturation: f32, luminosity: f32, alpha: f32) -> Self {
+        let [hue, saturation, luminosity, alpha] =
+            zero_non_finite([hue, saturation, luminosity, alpha]);
         let (red, green, blue, alpha) = hsl_to_rgb(hue, saturation, luminosity, alpha);
 
-        Self {
-            red: red.clamp(0.0, 255.0),
-            green: green.clamp(0.0, 255.0),
-            blue: blue.clamp(0.0, 255.0),
-            alpha: alpha.clamp(0.0, 1.0),
-            parsed_as: ColorType::Hsl,
-        }
+        Self::from_srgb(red, green, blue, alpha).with_type(ColorType::Hsl)
     }
 
+    /// Creates a colour from RGB components between 0 and 255.
+    ///
+    /// Like the other constructors, any NaN or infinite component is treated as zero.
+    /// Use `with_missing` for components that are missing, as with CSS's `none`.
     pub fn from_rgb(red: f32, green: f32, blue: f32, alpha: f32) -> Self {
+        Self::from_srgb(red / 255.0, green / 255.0, blue / 255.0, alpha)
+    }
+
+    /// Creates a colour from RGB components between 0 and 1, as they're stored.
+    fn from_srgb(red: f32, green: f32, blue: f32, alpha: f32) -> Self {
+        let [red, green, blue, alpha] = zero_non_finite([red, green, blue, alpha]);
+
         Self {
-            red: red.clamp(0.0, 255.0),
-            green: green.clamp(0.0, 255.0),
-            blue: blue.clamp(0.0, 255.0),
+            red: red.clamp(0.0, 1.0),
+            green: green.clamp(0.0, 1.0),
+            blue: blue.clamp(0.0, 1.0),
             alpha: alpha.clamp(0.0, 1.0),
             parsed_as: ColorType::Rgb,
+            missing: [false; 5],
+            space_components: None,
+        }
+    }
+
+    pub fn from_hwb(hue: f32, whiteness: f32, blackness: f32, alpha: f32) -> Self {
+        let [hue, whiteness, blackness, alpha] =
+            zero_non_finite([hue, whiteness, blackness, alpha]);
+        let (red, green, blue, alpha) = hwb_to_rgb(hue, whiteness, blackness, alpha);
+
+        Self::from_srgb(red, green, blue, alpha).with_type(ColorType::Hwb)
+    }
+
+    /// Creates a colour from HSV (also called HSB), with hue in degrees and saturation
+    /// and value between 0 and 100.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: f32) -> Self {
+        let [hue, saturation, value, alpha] = zero_non_finite([hue, saturation, value, alpha]);
+        let (red, green, blue, alpha) = hsv_to_rgb(hue, saturation, value, alpha);
+
+        Self::from_srgb(red, green, blue, alpha).with_type(ColorType::Hsv)
+    }
+
+    /// Creates a colour from 8-bit BT.709 YCbCr, as used for HD video, where luma
+    /// runs from 16 to 235 and the chroma components from 16 to 240.
+    pub fn from_ycbcr(luma: f32, cb: f32, cr: f32, alpha: f32) -> Self {
+        let [luma, cb, cr, alpha] = zero_non_finite([luma, cb, cr, alpha]);
+        let (red, green, blue) = ycbcr_to_rgb(luma, cb, cr);
+
+        Self::from_srgb(red, green, blue, alpha).with_type(ColorType::Ycbcr)
+    }
+
+    /// Creates a colour from CIE Lab components relative to a D50 white point, as
+    /// used by CSS `lab()`. Colours outside of sRGB are clamped.
+    pub fn from_lab(lightness: f32, a: f32, b: f32, alpha: f32) -> Self {
+        let [lightness, a, b, alpha] = zero_non_finite([lightness, a, b, alpha]);
+        let xyz = lab_to_xyz([lightness, a, b], D50_WHITE);
+        let (red, green, blue) = xyz_d50_to_rgb(xyz);
+
+        Self::from_srgb(red, green, blue, alpha)
+            .with_type(ColorType::Lab)
+            .with_space_components(ColorSpace::XyzD50, xyz)
+    }
+
+    /// Creates a colour from CIE LCh components, the polar form of [`Color::from_lab`].
+    pub fn from_lch(lightness: f32, chroma: f32, hue: f32, alpha: f32) -> Self {
+        let [lightness, chroma, hue, alpha] = zero_non_finite([lightness, chroma, hue, alpha]);
+        let [lightness, a, b] = lch_to_lab([lightness, chroma, hue]);
+
+        Self::from_lab(lightness, a, b, alpha).with_type(ColorType::Lch)
+    }
+
+    /// Creates a colour from CIE Lab components relative to a D65 white point, as
+    /// used by most image software outside of CSS. It displays as CSS `lab()`,
+    /// which is relative to D50. Colours outside of sRGB are clamped.
+    pub fn from_lab_d65(lightness: f32, a: f32, b: f32, alpha: f32) -> Self {
+        let [lightness, a, b, alpha] = zero_non_finite([lightness, a, b, alpha]);
+        let xyz = lab_to_xyz([lightness, a, b], D65_WHITE);
+        let (red, green, blue) = xyz_d65_to_rgb(xyz);
+
+        Self::from_srgb(red, green, blue, alpha)
+            .with_type(ColorType::Lab)
+            .with_space_components(ColorSpace::XyzD65, xyz)
+    }
+
+    /// Creates a colour from D65 CIE LCh components, the polar form of
+    /// [`Color::from_lab_d65`].
+    pub fn from_lch_d65(lightness: f32, chroma: f32, hue: f32, alpha: f32) -> Self {
+        let [lightness, chroma, hue, alpha] = zero_non_finite([lightness, chroma, hue, alpha]);
+        let [lightness, a, b] = lch_to_lab([lightness, chroma, hue]);
+
+        Self::from_lab_d65(lightness, a, b, alpha).with_type(ColorType::Lch)
+    }
+
+    /// Creates a colour from OKLab components, with lightness between 0 and 1.
+    /// Colours outside of sRGB are clamped.
+    pub fn from_oklab(lightness: f32, a: f32, b: f32, alpha: f32) -> Self {
+        let [lightness, a, b, alpha] = zero_non_finite([lightness, a, b, alpha]);
+        let linear = oklab_to_linear_srgb([lightness, a, b]);
+        let [red, green, blue] = linear.map(linear_to_srgb);
+
+        Self::from_srgb(red, green, blue, alpha)
+            .with_type(ColorType::Oklab)
+            .with_space_components(ColorSpace::SrgbLinear, linear)
+    }
+
+    /// Creates a colour from OKLCh components, the polar form of [`Color::from_oklab`].
+    pub fn from_oklch(lightness: f32, chroma: f32, hue: f32, alpha: f32) -> Self {
+        let [lightness, chroma, hue, alpha] = zero_non_finite([lightness, chroma, hue, alpha]);
+        let [lightness, a, b] = lch_to_lab([lightness, chroma, hue]);
+
+        Self::from_oklab(lightness, a, b, alpha).with_type(ColorType::Oklch)
+    }
+
+    /// Creates a colour from CMYK percentages using a naive conversion, with no ink
+    /// or paper profile.
+    pub fn from_cmyk(cyan: f32, magenta: f32, yellow: f32, black: f32, alpha: f32) -> Self {
+        let [cyan, magenta, yellow, black, alpha] =
+            zero_non_finite([cyan, magenta, yellow, black, alpha]);
+        let (red, green, blue) = cmyk_to_rgb(cyan, magenta, yellow, black);
+
+        Self::from_srgb(red, green, blue, alpha).with_type(ColorType::Cmyk)
+    }
+
+    /// Creates a colour from components in one of the CSS `color()` spaces, where
+    /// 0-1 covers the space's gamut. Colours outside of sRGB are clamped.
+    pub fn from_space(space: ColorSpace, c1: f32, c2: f32, c3: f32, alpha: f32) -> Self {
+        let [c1, c2, c3, alpha] = zero_non_finite([c1, c2, c3, alpha]);
+        let [red, green, blue] = space.to_linear_srgb([c1, c2, c3]).map(linear_to_srgb);
+
+        Self::from_srgb(red, green, blue, alpha)
+            .with_type(ColorType::Color(space))
+            .with_space_components(space, [c1, c2, c3])
+    }
+
+    /// Like [`Color::from_space`], but bringing colours outside of sRGB, such as
+    /// saturated Display P3 ones, into it with `mapping`. See `gamut_map`.
+    pub fn from_space_mapped(
+        space: ColorSpace,
+        c1: f32,
+        c2: f32,
+        c3: f32,
+        alpha: f32,
+        mapping: GamutMapping,
+    ) -> Self {
+        Self::from_space(space, c1, c2, c3, alpha).gamut_map(mapping)
+    }
+
+    /// Creates a colour from linear-light sRGB components between 0 and 1, such as the
+    /// result of mixing colours from `linear_rgb`.
+    pub fn from_linear_rgb(red: f32, green: f32, blue: f32, alpha: f32) -> Self {
+        Self::from_space(ColorSpace::SrgbLinear, red, green, blue, alpha)
+    }
+
+    /// Creates a colour from CIE XYZ relative to `illuminant`, where Y is 1 for
+    /// white. Colours outside of sRGB are clamped.
+    pub fn from_xyz(illuminant: Illuminant, x: f32, y: f32, z: f32, alpha: f32) -> Self {
+        Self::from_space(illuminant.xyz_space(), x, y, z, alpha)
+    }
+
+    /// Approximates the colour of a black body at `kelvin`, such as 6500 for
+    /// daylight. Accurate from 1000K to 15000K. The result is scaled so that its
+    /// brightest channel is at full intensity.
+    pub fn from_kelvin(kelvin: f32) -> Self {
+        let (red, green, blue) = kelvin_to_rgb(kelvin);
+        Self::from_srgb(red, green, blue, 1.0).with_type(ColorType::Hex)
+    }
+
+    /// Approximates the colour of monochromatic light with a wavelength of
+    /// `nanometres`, between 380 and 780. Spectral colours are more saturated than
+    /// sRGB can show, so the result is clipped into gamut and shown at full
+    /// brightness.
+    pub fn from_wavelength(nanometres: f32) -> Self {
+        let (red, green, blue) = wavelength_to_rgb(nanometres);
+        Self::from_srgb(red, green, blue, 1.0).with_type(ColorType::Hex)
+    }
+
+    /// The colour at `index` in the standard xterm 256 colour palette.
+    pub fn from_ansi(index: u8) -> Self {
+        let (red, green, blue) = crate::ansi::palette(index);
+        Self::from_rgb(red, green, blue, 1.0).with_type(ColorType::Ansi)
+    }
+
+    /// Unpacks a colour stored in an integer as `0xRRGGBB`. The top byte is ignored.
+    pub fn from_u32_rgb(value: u32) -> Self {
+        let [_, red, green, blue] = value.to_be_bytes();
+        Self::from_rgb(red as f32, green as f32, blue as f32, 1.0).with_type(ColorType::Hex)
+    }
+
+    /// Unpacks a colour stored in an integer as `0xRRGGBBAA`.
+    pub fn from_u32_rgba(value: u32) -> Self {
+        let [red, green, blue, alpha] = value.to_be_bytes();
+        Self::from_rgb(red as f32, green as f32, blue as f32, alpha as f32 / 255.0)
+            .with_type(ColorType::Hex)
+    }
+
+    /// Parses a hex colour in `RGB`, `RGBA`, `RRGGBB` or `RRGGBBAA` form, with or
+    /// without a leading `#`.
+    pub fn from_hex(hex: &str) -> Result<Self, crate::Error> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        let (_, (red, green, blue, alpha)) =
+            nom::combinator::all_consuming(crate::parse::hex_digits)(digits)
+                .map_err(|err| crate::parse::invalid_color(hex, err))?;
+
+        Ok(Self::from_rgb(red, green, blue, alpha).with_type(ColorType::Hex))
+    }
+
+    /// Looks up a CSS named colour such as `tomato`, ignoring case.
+    pub fn from_name(name: &str) -> Result<Self, crate::Error> {
+        let (red, green, blue, alpha) =
+            crate::names::lookup(name).ok_or_else(|| crate::Error::UnknownColorName {
+                name: name.to_string(),
+                suggestion: crate::suggest::closest(&name.to_lowercase(), crate::names::names()),
+            })?;
+
+        Ok(Self::from_rgb(red, green, blue, alpha).with_type(ColorType::Name))
+    }
+
+    /// Reads a pronounceable code from `to_phonetic`, such as `kato-rimu`. Case,
+    /// hyphens and spaces are ignored.
+    pub fn from_phonetic(code: &str) -> Result<Self, crate::Error> {
+        crate::phonetic::decode(code)
+            .map(Self::from_u32_rgb)
+            .ok_or_else(|| crate::Error::InvalidPhoneticCode(code.to_string()))
+    }
+
+    /// Parses a colour using only the grammar for `color_type`, rather than trying
+    /// every notation. Hex colours may omit the leading `#`.
+    pub fn parse_as(input: &str, color_type: ColorType) -> Result<Self, crate::Error> {
+        let (_, color) = crate::parse::parse_color_as(input, color_type).map_err(|_| {
+            crate::Error::InvalidColorAs {
+                input: input.to_string(),
+                color_type,
+            }
+        })?;
+
+        Ok(color)
+    }
+
+    /// Parses a colour only if it is valid CSS. Unlike `from_str` this rejects
+    /// notations CSS doesn't have such as `hsv()`, legacy comma syntax mixed with
+    /// modern features, and values outside the ranges CSS would clamp them to.
+    pub fn from_str_strict(input: &str) -> Result<Self, crate::Error> {
+        match crate::parse::parse_color_strict(input) {
+            Ok((_, color)) => Ok(color),
+            Err(_) if !input.is_empty() && input.chars().all(|c| c.is_ascii_alphabetic()) => {
+                Self::from_name(input)
+            }
+            Err(err) => Err(crate::parse::invalid_color(input, err)),
         }
     }
 
-    pub fn from_hex(hex: &str) -> Self {
-        todo!()
+    /// Parses a colour like `from_str`, but also accepts sloppy input as found in
+    /// the wild: hex without the `#`, functions without brackets (`rgb 255 0 0`),
+    /// mixed comma and space separators, and a trailing `;` copied from CSS.
+    pub fn from_str_lenient(input: &str) -> Result<Self, crate::Error> {
+        let trimmed = input.trim().trim_end_matches(';').trim_end();
+        // Otherwise all-digit codes such as `336699` would be read as packed decimals
+        if crate::parse::is_bare_hex(trimmed) {
+            return crate::parse::tidy(trimmed).parse();
+        }
+
+        match trimmed.parse() {
+            Ok(color) => Ok(color),
+            Err(err) => crate::parse::tidy(trimmed).parse().map_err(|_| err),
+        }
+    }
+
+    /// The notation the colour was created from, which it is displayed in.
+    pub fn color_type(&self) -> ColorType {
+        self.parsed_as
+    }
+
+    /// The CSS `currentcolor` keyword. Until it's replaced with
+    /// `resolve_current_color` it behaves as black, the usual default text colour.
+    pub fn current_color() -> Self {
+        Self::from_rgb(0.0, 0.0, 0.0, 1.0).with_type(ColorType::CurrentColor)
+    }
+
+    pub fn is_current_color(&self) -> bool {
+        self.parsed_as == ColorType::CurrentColor
+    }
+
+    /// Replaces `currentcolor` with `current`, leaving any other colour unchanged.
+    pub fn resolve_current_color(self, current: &Color) -> Self {
+        if self.is_current_color() {
+            current.clone()
+        } else {
+            self
+        }
+    }
+
+    /// Passes the colour through a 3D LUT, keeping its alpha and notation.
+    pub fn apply_lut(&self, lut: &crate::lut::Lut3d) -> Self {
+        let [red, green, blue] = lut.apply([self.red, self.green, self.blue]);
+
+        Self::from_srgb(red, green, blue, self.alpha).with_type(self.parsed_as)
+    }
+
+    /// The perceived difference between two colours, using the CIEDE2000 formula.
+    /// Differences below about 2 are hard to see. Alpha is ignored.
+    pub fn delta_e(&self, other: &Color) -> f32 {
+        let lab = |c: &Color| xyz_to_lab(rgb_to_xyz_d50(c.red, c.green, c.blue), D50_WHITE);
+        ciede2000(lab(self), lab(other))
+    }
+
+    /// Snaps the colour to a nearby one with round HSL values, if there is one
+    /// within a barely noticeable difference. See `beautify_within`.
+    pub fn beautify(&self) -> Self {
+        self.beautify_within(BEAUTIFY_MAX_DELTA_E)
+    }
+
+    /// Snaps the colour to the closest one whose hue is a multiple of 5° and whose
+    /// saturation and lightness are multiples of 5%, as long as it's no more than
+    /// `max_delta_e` away. Otherwise the colour is returned unchanged.
+    pub fn beautify_within(&self, max_delta_e: f32) -> Self {
+        let (hue, saturation, luminosity, alpha) =
+            rgb_to_hsl(self.red, self.green, self.blue, self.alpha);
+        let steps = |n: f32| [(n / 5.0).floor() * 5.0, (n / 5.0).ceil() * 5.0];
+
+        let mut candidates = Vec::new();
+        for h in steps(hue) {
+            for s in steps(saturation) {
+                for l in steps(luminosity) {
+                    candidates.push(Self::from_hsl(h, s, l, alpha));
+                }
+            }
+        }
+
+        candidates
+            .into_iter()
+            .map(|candidate| (self.delta_e(&candidate), candidate))
+            .filter(|(delta_e, _)| *delta_e <= max_delta_e)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map_or_else(|| self.clone(), |(_, c)| c.with_type(self.parsed_as))
+    }
+
+    pub(crate) fn with_type(self, parsed_as: ColorType) -> Self {
+        Self { parsed_as, ..self }
+    }
+
+    fn with_space_components(self, space: ColorSpace, components: [f32; 3]) -> Self {
+        Self {
+            space_components: Some((space, components)),
+            ..self
+        }
+    }
+
+    /// Whether sRGB can show the colour as it was given. Colours from a `color()`
+    /// space, Lab or OKLab may be outside of it, and are clamped for sRGB notations.
+    pub fn in_srgb_gamut(&self) -> bool {
+        in_srgb_gamut(self.unclamped_linear_rgb())
+    }
+
+    /// Brings a colour that's outside of sRGB into it with `mapping`, keeping its
+    /// notation. The result is written out as the mapped colour, not the original.
+    pub fn gamut_map(&self, mapping: GamutMapping) -> Self {
+        let linear = self.unclamped_linear_rgb();
+        let linear = match mapping {
+            GamutMapping::Clip => linear,
+            GamutMapping::ReduceChroma => reduce_chroma_into_srgb(linear),
+        };
+        let [red, green, blue] = linear.map(linear_to_srgb);
+
+        Self {
+            missing: self.missing,
+            ..Self::from_srgb(red, green, blue, self.alpha).with_type(self.parsed_as)
+        }
+    }
+
+    /// Marks components as missing, as with CSS's `none` keyword, so they're written
+    /// back out as `none` in the colour's own notation. `missing` has a flag for each
+    /// of the notation's components in order and then one for alpha, so `[true, false,
+    /// false, false]` marks an HSL colour's hue. Missing components should be given to
+    /// the constructor as zero, which is how CSS converts them.
+    pub fn with_missing(self, missing: &[bool]) -> Self {
+        let mut flags = [false; 5];
+        for (flag, missing) in flags.iter_mut().zip(missing) {
+            *flag = *missing;
+        }
+
+        Self {
+            missing: flags,
+            ..self
+        }
+    }
+
+    /// The `missing` flags that apply when writing the colour as `color_type`.
+    fn missing_in(&self, color_type: ColorType) -> [bool; 5] {
+        if self.parsed_as == color_type {
+            self.missing
+        } else {
+            [false; 5]
+        }
     }
 
     pub fn rgb(&self) -> (f32, f32, f32, f32) {
+        let [red, green, blue] = self.unrounded_rgb().map(f32::round);
+
+        (red, green, blue, self.alpha)
+    }
+
+    /// The RGB components between 0 and 255, before they're rounded by `rgb`.
+    fn unrounded_rgb(&self) -> [f32; 3] {
+        [self.red, self.green, self.blue].map(|c| c * 255.0)
+    }
+
+    pub fn hsl(&self) -> (f32, f32, f32, f32) {
         let Self {
             red,
             green,
@@ -96,10 +765,11 @@ impl Color {
             ..
         } = *self;
 
-        (red, green, blue, alpha)
+        rgb_to_hsl(red, green, blue, alpha)
     }
 
-    pub fn hsl(&self) -> (f32, f32, f32, f32) {
+    /// The colour as HSV, in the ranges taken by [`Color::from_hsv`].
+    pub fn hsv(&self) -> (f32, f32, f32, f32) {
         let Self {
             red,
             green,
@@ -108,10 +778,11 @@ impl Color {
             ..
         } = *self;
 
-        rgb_to_hsl(red, green, blue, alpha)
+        rgb_to_hsv(red, green, blue, alpha)
     }
 
-    pub fn rgb_string(&self) -> String {
+    /// The colour as 8-bit BT.709 YCbCr. See `from_ycbcr`.
+    pub fn ycbcr(&self) -> (f32, f32, f32, f32) {
         let Self {
             red,
             green,
@@ -120,86 +791,454 @@ impl Color {
             ..
         } = *self;
 
-        if (alpha - 1.0).abs() < f32::EPSILON {
-            format!("rgb({} {} {})", red, green, blue)
-        } else {
-            format!("rgb({} {} {} / {})", red, green, blue, alpha)
-        }
+        let [luma, cb, cr] = rgb_to_ycbcr(red, green, blue).map(round_to_one_decimal_place);
+
+        (luma, cb, cr, alpha)
     }
 
-    pub fn hsl_string(&self) -> String {
-        let (hue, sat, lum, alpha) = self.hsl();
+    /// The naive CMYK percentages for the colour. Alpha is dropped.
+    pub fn cmyk(&self) -> (f32, f32, f32, f32) {
+        let Self {
+            red, green, blue, ..
+        } = *self;
 
-        if (alpha - 1.0).abs() < f32::EPSILON {
-            format!("hsl({} {} {})", hue, sat, lum)
-        } else {
-            format!("hsl({} {} {} / {})", hue, sat, lum, alpha)
-        }
+        rgb_to_cmyk(red, green, blue)
     }
 
-    pub fn hex_string(&self) -> String {
-        let (red, green, blue, alpha) = self.rgb();
-        rgb_to_hex(red, green, blue, alpha)
+    pub fn hwb(&self) -> (f32, f32, f32, f32) {
+        let Self {
+            red,
+            green,
+            blue,
+            alpha,
+            ..
+        } = *self;
+
+        rgb_to_hwb(red, green, blue, alpha)
     }
-}
 
-fn rgb_to_hsl(red: f32, green: f32, blue: f32, alpha: f32) -> (f32, f32, f32, f32) {
-    let red = red.clamp(0.0, 255.0) / 255.0;
-    let green = green.clamp(0.0, 255.0) / 255.0;
-    let blue = blue.clamp(0.0, 255.0) / 255.0;
-    let alpha = alpha.clamp(0.0, 1.0);
+    pub fn lab(&self) -> (f32, f32, f32, f32) {
+        let [lightness, a, b] = xyz_to_lab(self.unclamped_xyz(Illuminant::D50), D50_WHITE);
 
-    let cmin = [red, green, blue].into_iter().reduce(f32::min).unwrap();
-    let cmax = [red, green, blue].into_iter().reduce(f32::max).unwrap();
-    let delta = cmax - cmin;
+        (
+            round_to_one_decimal_place(lightness),
+            round_to_one_decimal_place(a),
+            round_to_one_decimal_place(b),
+            self.alpha,
+        )
+    }
 
-    let mut hue;
-    let mut saturation;
-    let mut luminosity;
+    pub fn lch(&self) -> (f32, f32, f32, f32) {
+        let lab = xyz_to_lab(self.unclamped_xyz(Illuminant::D50), D50_WHITE);
+        let [lightness, chroma, hue] = lab_to_lch(lab);
 
-    // hue
-    if delta == 0.0 {
-        hue = 0.0;
-    } else if (cmax - red).abs() < f32::EPSILON {
-        hue = ((green - blue) / delta) % 6.0;
-    } else if (cmax - green).abs() < f32::EPSILON {
-        hue = (blue - red) / delta + 2.0;
-    } else {
-        hue = (red - green) / delta + 4.0;
+        (
+            round_to_one_decimal_place(lightness),
+            round_to_one_decimal_place(chroma),
+            round_to_one_decimal_place(hue),
+            self.alpha,
+        )
     }
 
-    hue = (hue * 60.0).round();
+    /// The colour as CIE Lab relative to a D65 white point. See `from_lab_d65`.
+    pub fn lab_d65(&self) -> (f32, f32, f32, f32) {
+        let [lightness, a, b] = xyz_to_lab(self.unclamped_xyz(Illuminant::D65), D65_WHITE);
 
-    if hue < 0.0 {
-        hue += 360.0;
+        (
+            round_to_one_decimal_place(lightness),
+            round_to_one_decimal_place(a),
+            round_to_one_decimal_place(b),
+            self.alpha,
+        )
     }
 
-    // luminosity
-    luminosity = (cmax + cmin) / 2.0;
+    /// The colour as D65 CIE LCh, the polar form of `lab_d65`.
+    pub fn lch_d65(&self) -> (f32, f32, f32, f32) {
+        let lab = xyz_to_lab(self.unclamped_xyz(Illuminant::D65), D65_WHITE);
+        let [lightness, chroma, hue] = lab_to_lch(lab);
 
-    // saturation
-    saturation = if delta == 0.0 {
-        0.0
-    } else {
-        delta / (1.0 - (2.0 * luminosity - 1.0).abs())
-    };
+        (
+            round_to_one_decimal_place(lightness),
+            round_to_one_decimal_place(chroma),
+            round_to_one_decimal_place(hue),
+            self.alpha,
+        )
+    }
 
-    saturation *= 100.0;
-    luminosity *= 100.0;
+    /// The colour as OKLab, in the ranges taken by [`Color::from_oklab`].
+    pub fn oklab(&self) -> (f32, f32, f32, f32) {
+        let [lightness, a, b] = linear_srgb_to_oklab(self.unclamped_linear_rgb());
 
-    (
-        round_to_one_decimal_place(hue),
-        round_to_one_decimal_place(saturation),
-        round_to_one_decimal_place(luminosity),
-        alpha,
-    )
-}
+        (
+            round_to_three_decimal_places(lightness),
+            round_to_three_decimal_places(a),
+            round_to_three_decimal_places(b),
+            self.alpha,
+        )
+    }
 
-fn hsl_to_rgb(hue: f32, saturation: f32, luminosity: f32, alpha: f32) -> (f32, f32, f32, f32) {
-    let hue = hue.clamp(0.0, 360.0);
-    let saturation = saturation.clamp(0.0, 100.0);
-    let luminosity = luminosity.clamp(0.0, 100.0);
-    let alpha = alpha.clamp(0.0, 1.0);
+    /// The colour as OKLCh, with hue in degrees. Useful for adjusting lightness or
+    /// chroma while keeping the perceived hue.
+    pub fn oklch(&self) -> (f32, f32, f32, f32) {
+        let oklab = linear_srgb_to_oklab(self.unclamped_linear_rgb());
+        let [lightness, chroma, hue] = lab_to_lch(oklab);
+
+        (
+            round_to_three_decimal_places(lightness),
+            round_to_three_decimal_places(chroma),
+            round_to_one_decimal_place(hue),
+            self.alpha,
+        )
+    }
+
+    /// The colour's components in one of the CSS `color()` spaces. A colour given in
+    /// `space` gets back exactly the components it was created from.
+    pub fn in_space(&self, space: ColorSpace) -> (f32, f32, f32, f32) {
+        let [c1, c2, c3] = match self.space_components {
+            Some((original, components)) if original == space => components,
+            _ => space.convert_from_linear_srgb(self.unclamped_linear_rgb()),
+        };
+
+        (
+            round_to_three_decimal_places(c1),
+            round_to_three_decimal_places(c2),
+            round_to_three_decimal_places(c3),
+            self.alpha,
+        )
+    }
+
+    /// The colour in linear-light sRGB, with the sRGB transfer function removed.
+    /// Components are between 0 and 1 and aren't rounded. Unlike the gamma-encoded
+    /// values from `rgb`, these can be added, averaged or blended physically.
+    pub fn linear_rgb(&self) -> (f32, f32, f32, f32) {
+        let [red, green, blue] = [self.red, self.green, self.blue].map(srgb_to_linear);
+
+        (red, green, blue, self.alpha)
+    }
+
+    /// The colour as CIE XYZ relative to `illuminant`. See `from_xyz`. Unlike the
+    /// other accessors the components aren't rounded, as they're mostly used for
+    /// further calculation.
+    pub fn xyz(&self, illuminant: Illuminant) -> (f32, f32, f32, f32) {
+        let [x, y, z] = self.unclamped_xyz(illuminant);
+
+        (x, y, z, self.alpha)
+    }
+
+    /// Linear-light sRGB that isn't clamped to the sRGB gamut, so colours given in a
+    /// wider space keep their saturation when converted to another one.
+    fn unclamped_linear_rgb(&self) -> [f32; 3] {
+        match self.space_components {
+            Some((space, components)) => space.to_linear_srgb(components),
+            None => [self.red, self.green, self.blue].map(srgb_to_linear),
+        }
+    }
+
+    fn unclamped_xyz(&self, illuminant: Illuminant) -> [f32; 3] {
+        illuminant
+            .xyz_space()
+            .convert_from_linear_srgb(self.unclamped_linear_rgb())
+    }
+
+    /// Adapts the colour from one white point to another with the Bradford
+    /// transform, treating its XYZ values as relative to `from` so that `from`'s white
+    /// becomes `to`'s. This reconciles values measured or specified under a different
+    /// light without adaptation, such as print measurements taken under D50. Colours
+    /// outside of sRGB stay outside of it.
+    pub fn adapt(&self, from: Illuminant, to: Illuminant) -> Self {
+        let xyz = self.unclamped_xyz(Illuminant::D65);
+        let [x, y, z] = multiply(&bradford(from, to), xyz);
+
+        Self {
+            missing: self.missing,
+            ..Self::from_xyz(Illuminant::D65, x, y, z, self.alpha).with_type(self.parsed_as)
+        }
+    }
+
+    pub fn rgb_string(&self) -> String {
+        let (red, green, blue, alpha) = self.rgb();
+
+        self.function_string("rgb(", ColorType::Rgb, [red, green, blue], alpha)
+    }
+
+    pub fn hsl_string(&self) -> String {
+        let (c1, c2, c3, alpha) = self.hsl();
+
+        self.function_string("hsl(", ColorType::Hsl, [c1, c2, c3], alpha)
+    }
+
+    pub fn hwb_string(&self) -> String {
+        let (c1, c2, c3, alpha) = self.hwb();
+
+        self.function_string("hwb(", ColorType::Hwb, [c1, c2, c3], alpha)
+    }
+
+    pub fn hex_string(&self) -> String {
+        let (red, green, blue, alpha) = self.rgb();
+        rgb_to_hex(red, green, blue, alpha)
+    }
+
+    pub fn hsv_string(&self) -> String {
+        let (c1, c2, c3, alpha) = self.hsv();
+
+        self.function_string("hsv(", ColorType::Hsv, [c1, c2, c3], alpha)
+    }
+
+    pub fn ycbcr_string(&self) -> String {
+        let (c1, c2, c3, alpha) = self.ycbcr();
+
+        self.function_string("ycbcr(", ColorType::Ycbcr, [c1, c2, c3], alpha)
+    }
+
+    pub fn lab_string(&self) -> String {
+        let (c1, c2, c3, alpha) = self.lab();
+
+        self.function_string("lab(", ColorType::Lab, [c1, c2, c3], alpha)
+    }
+
+    pub fn lch_string(&self) -> String {
+        let (c1, c2, c3, alpha) = self.lch();
+
+        self.function_string("lch(", ColorType::Lch, [c1, c2, c3], alpha)
+    }
+
+    pub fn oklab_string(&self) -> String {
+        let (c1, c2, c3, alpha) = self.oklab();
+
+        self.function_string("oklab(", ColorType::Oklab, [c1, c2, c3], alpha)
+    }
+
+    pub fn oklch_string(&self) -> String {
+        let (c1, c2, c3, alpha) = self.oklch();
+
+        self.function_string("oklch(", ColorType::Oklch, [c1, c2, c3], alpha)
+    }
+
+    pub fn color_string(&self, space: ColorSpace) -> String {
+        let (c1, c2, c3, alpha) = self.in_space(space);
+        let prefix = format!("color({} ", space);
+
+        self.function_string(&prefix, ColorType::Color(space), [c1, c2, c3], alpha)
+    }
+
+    pub fn cmyk_string(&self) -> String {
+        let (cyan, magenta, yellow, black) = self.cmyk();
+        let missing = self.missing_in(ColorType::Cmyk);
+        let ink = |i: usize, value: f32| {
+            if missing[i] {
+                "none".to_string()
+            } else {
+                format!("{}%", value)
+            }
+        };
+        let inks = format!(
+            "{} {} {} {}",
+            ink(0, cyan),
+            ink(1, magenta),
+            ink(2, yellow),
+            ink(3, black)
+        );
+
+        if missing[4] {
+            format!("device-cmyk({} / none)", inks)
+        } else if (self.alpha - 1.0).abs() < f32::EPSILON {
+            format!("device-cmyk({})", inks)
+        } else {
+            format!("device-cmyk({} / {})", inks, self.alpha)
+        }
+    }
+
+    /// Formats a CSS function such as `hsl()` in its modern, space separated form.
+    /// If the colour was parsed as `color_type` then any components that were
+    /// missing are written back out as `none`.
+    fn function_string(
+        &self,
+        prefix: &str,
+        color_type: ColorType,
+        components: [f32; 3],
+        alpha: f32,
+    ) -> String {
+        let missing = self.missing_in(color_type);
+        let component = |i: usize, value: f32| {
+            if missing[i] {
+                "none".to_string()
+            } else {
+                value.to_string()
+            }
+        };
+
+        let [c1, c2, c3] = components;
+        let components = format!(
+            "{} {} {}",
+            component(0, c1),
+            component(1, c2),
+            component(2, c3)
+        );
+
+        if (alpha - 1.0).abs() < f32::EPSILON && !missing[3] {
+            format!("{}{})", prefix, components)
+        } else {
+            format!("{}{} / {})", prefix, components, component(3, alpha))
+        }
+    }
+
+    /// The CSS name of the colour if it has one, or its hex string otherwise.
+    pub fn name_string(&self) -> String {
+        let (red, green, blue, alpha) = self.rgb();
+
+        match crate::names::name_of(red, green, blue, alpha) {
+            Some(name) => name.to_string(),
+            None => self.hex_string(),
+        }
+    }
+
+    /// The decimal colour integer used by Office automation, as returned by
+    /// VBA's `RGB()`. Note that red is the least significant byte. Alpha is
+    /// dropped.
+    pub fn office_string(&self) -> String {
+        let (red, green, blue, _) = self.rgb();
+        let value = red.round() as u32 | (green.round() as u32) << 8 | (blue.round() as u32) << 16;
+
+        value.to_string()
+    }
+
+    pub fn vba_string(&self) -> String {
+        let (red, green, blue, _) = self.rgb();
+
+        format!(
+            "RGB({}, {}, {})",
+            red.round() as u32,
+            green.round() as u32,
+            blue.round() as u32
+        )
+    }
+
+    /// An xcolor `\definecolor` using the `RGB` model. Alpha is dropped.
+    pub fn latex_string(&self, name: &str) -> String {
+        let (red, green, blue, _) = self.rgb();
+
+        format!(
+            "\\definecolor{{{}}}{{RGB}}{{{},{},{}}}",
+            name,
+            red.round() as u32,
+            green.round() as u32,
+            blue.round() as u32
+        )
+    }
+
+    /// An xcolor `\definecolor` using the `HTML` model. Alpha is dropped.
+    pub fn latex_html_string(&self, name: &str) -> String {
+        let (red, green, blue, _) = self.rgb();
+
+        format!(
+            "\\definecolor{{{}}}{{HTML}}{{{}}}",
+            name,
+            &rgb_to_hex(red, green, blue, 1.0)[1..]
+        )
+    }
+
+    pub fn typst_string(&self) -> String {
+        format!("rgb(\"{}\")", self.hex_string())
+    }
+
+    /// The closest entry in the xterm 256 colour palette, e.g. `ansi:208`. Alpha is
+    /// dropped.
+    pub fn ansi_string(&self) -> String {
+        let (red, green, blue, _) = self.rgb();
+        format!("ansi:{}", crate::ansi::nearest(red, green, blue))
+    }
+
+    /// The coloured square emoji closest to the colour, e.g. `🟧`, for sharing colours
+    /// in chat and comments where they can't be shown directly. Alpha is ignored.
+    pub fn nearest_emoji(&self) -> &'static str {
+        crate::emoji::nearest(self)
+    }
+
+    /// The colour as a short pronounceable code such as `kato-rimu`, for reading a
+    /// colour out over a call. Alpha is dropped. See `from_phonetic`.
+    pub fn to_phonetic(&self) -> String {
+        let (red, green, blue, _) = self.rgb();
+
+        crate::phonetic::encode(u32::from_be_bytes([0, red as u8, green as u8, blue as u8]))
+    }
+
+    /// A groff `.defcolor` request. Alpha is dropped.
+    pub fn troff_string(&self, name: &str) -> String {
+        let (red, green, blue, _) = self.rgb();
+
+        format!(
+            ".defcolor {} rgb {}",
+            name,
+            rgb_to_hex(red, green, blue, 1.0)
+        )
+    }
+}
+
+/// Replaces NaN and infinite components with zero, so every constructor treats them
+/// the same way. Use `Color::with_missing` to mark components as CSS's `none`.
+fn zero_non_finite<const N: usize>(components: [f32; N]) -> [f32; N] {
+    components.map(|c| if c.is_finite() { c } else { 0.0 })
+}
+
+fn rgb_to_hsl(red: f32, green: f32, blue: f32, alpha: f32) -> (f32, f32, f32, f32) {
+    let alpha = alpha.clamp(0.0, 1.0);
+
+    let cmin = [red, green, blue].into_iter().reduce(f32::min).unwrap();
+    let cmax = [red, green, blue].into_iter().reduce(f32::max).unwrap();
+    let delta = cmax - cmin;
+
+    let mut hue;
+    let mut saturation;
+    let mut luminosity;
+
+    // hue
+    if delta == 0.0 {
+        hue = 0.0;
+    } else if (cmax - red).abs() < f32::EPSILON {
+        hue = ((green - blue) / delta) % 6.0;
+    } else if (cmax - green).abs() < f32::EPSILON {
+        hue = (blue - red) / delta + 2.0;
+    } else {
+        hue = (red - green) / delta + 4.0;
+    }
+
+    hue = (hue * 60.0).round();
+
+    if hue < 0.0 {
+        hue += 360.0;
+    }
+
+    // luminosity
+    luminosity = (cmax + cmin) / 2.0;
+
+    // saturation
+    saturation = if delta == 0.0 {
+        0.0
+    } else {
+        delta / (1.0 - (2.0 * luminosity - 1.0).abs())
+    };
+
+    saturation *= 100.0;
+    luminosity *= 100.0;
+
+    (
+        round_to_one_decimal_place(hue),
+        round_to_one_decimal_place(saturation),
+        round_to_one_decimal_place(luminosity),
+        alpha,
+    )
+}
+
+fn hsl_to_rgb(hue: f32, saturation: f32, luminosity: f32, alpha: f32) -> (f32, f32, f32, f32) {
+    // Hue is an angle, so it wraps around rather than clamping, as in CSS
+    let hue = if hue.is_finite() {
+        hue.rem_euclid(360.0)
+    } else {
+        0.0
+    };
+    let saturation = saturation.clamp(0.0, 100.0);
+    let luminosity = luminosity.clamp(0.0, 100.0);
+    let alpha = alpha.clamp(0.0, 1.0);
 
     let saturation = saturation / 100.0;
     let luminosity = luminosity / 100.0;
@@ -236,55 +1275,1381 @@ fn hsl_to_rgb(hue: f32, saturation: f32, luminosity: f32, alpha: f32) -> (f32, f
         green = 0.0;
         blue = x;
     } else {
-        unreachable!("HSL hue is clamped to 0..=360")
+        unreachable!("HSL hue is wrapped into 0..360")
     }
 
-    let red = ((red + lightness) * 255.0).round();
-    let green = ((green + lightness) * 255.0).round();
-    let blue = ((blue + lightness) * 255.0).round();
+    (red + lightness, green + lightness, blue + lightness, alpha)
+}
+
+fn rgb_to_hwb(red: f32, green: f32, blue: f32, alpha: f32) -> (f32, f32, f32, f32) {
+    let (hue, _, _, alpha) = rgb_to_hsl(red, green, blue, alpha);
 
-    (red, green, blue, alpha)
+    let whiteness = [red, green, blue].into_iter().reduce(f32::min).unwrap();
+    let blackness = 1.0 - [red, green, blue].into_iter().reduce(f32::max).unwrap();
+
+    (
+        hue,
+        round_to_one_decimal_place(whiteness * 100.0),
+        round_to_one_decimal_place(blackness * 100.0),
+        alpha,
+    )
 }
 
-fn round_to_one_decimal_place(n: f32) -> f32 {
-    (n * 10.0).round() / 10.0
+fn hwb_to_rgb(hue: f32, whiteness: f32, blackness: f32, alpha: f32) -> (f32, f32, f32, f32) {
+    let whiteness = whiteness.clamp(0.0, 100.0) / 100.0;
+    let blackness = blackness.clamp(0.0, 100.0) / 100.0;
+    let alpha = alpha.clamp(0.0, 1.0);
+
+    // Whiteness and blackness that add up to more than 100% produce a grey
+    if whiteness + blackness >= 1.0 {
+        let grey = whiteness / (whiteness + blackness);
+        return (grey, grey, grey, alpha);
+    }
+
+    let (red, green, blue, _) = hsl_to_rgb(hue, 100.0, 50.0, 1.0);
+    let scale = |c: f32| c * (1.0 - whiteness - blackness) + whiteness;
+
+    (scale(red), scale(green), scale(blue), alpha)
 }
 
-fn rgb_to_hex(red: f32, green: f32, blue: f32, alpha: f32) -> String {
-    if (alpha - 1.0).abs() < f32::EPSILON {
-        format!(
-            "#{:02X}{:02X}{:02X}",
-            red.round() as i64,
-            green.round() as i64,
-            blue.round() as i64
-        )
+fn rgb_to_hsv(red: f32, green: f32, blue: f32, alpha: f32) -> (f32, f32, f32, f32) {
+    let (hue, _, _, alpha) = rgb_to_hsl(red, green, blue, alpha);
+
+    let cmin = [red, green, blue].into_iter().reduce(f32::min).unwrap();
+    let cmax = [red, green, blue].into_iter().reduce(f32::max).unwrap();
+
+    let saturation = if cmax == 0.0 {
+        0.0
     } else {
-        let alpha = (alpha * 255.0).round() as i64;
-        format!(
-            "#{:02X}{:02X}{:02X}{:02X}",
-            red.round() as i64,
-            green.round() as i64,
-            blue.round() as i64,
-            alpha
-        )
-    }
+        (cmax - cmin) / cmax
+    };
+
+    (
+        hue,
+        round_to_one_decimal_place(saturation * 100.0),
+        round_to_one_decimal_place(cmax * 100.0),
+        alpha,
+    )
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32, alpha: f32) -> (f32, f32, f32, f32) {
+    let saturation = saturation.clamp(0.0, 100.0) / 100.0;
+    let value = value.clamp(0.0, 100.0) / 100.0;
 
-    #[test]
-    fn convert_rgb_to_hsl() {
-        let color = Color::from_rgb(23.0, 11.0, 33.0, 1.0);
+    // HSV and HSL share a hue, so convert the other two components across
+    let luminosity = value * (1.0 - saturation / 2.0);
+    let hsl_saturation = if luminosity == 0.0 || luminosity == 1.0 {
+        0.0
+    } else {
+        (value - luminosity) / luminosity.min(1.0 - luminosity)
+    };
 
-        assert_eq!((273.0, 50.0, 8.6, 1.0), color.hsl());
+    hsl_to_rgb(hue, hsl_saturation * 100.0, luminosity * 100.0, alpha)
+}
+
+fn rgb_to_cmyk(red: f32, green: f32, blue: f32) -> (f32, f32, f32, f32) {
+    let black = 1.0 - [red, green, blue].into_iter().reduce(f32::max).unwrap();
+
+    // Pure black has no chromatic ink at all
+    if black >= 1.0 {
+        return (0.0, 0.0, 0.0, 100.0);
     }
 
-    #[test]
-    fn convert_hsl_to_rgb() {
-        let color = Color::from_hsl(122.0, 33.0, 12.0, 0.4);
+    let ink = |c: f32| round_to_one_decimal_place((1.0 - c - black) / (1.0 - black) * 100.0);
 
-        assert_eq!((21.0, 41.0, 21.0, 0.4), color.rgb());
+    (
+        ink(red),
+        ink(green),
+        ink(blue),
+        round_to_one_decimal_place(black * 100.0),
+    )
+}
+
+fn cmyk_to_rgb(cyan: f32, magenta: f32, yellow: f32, black: f32) -> (f32, f32, f32) {
+    let black = black.clamp(0.0, 100.0) / 100.0;
+    let channel = |ink: f32| (1.0 - ink.clamp(0.0, 100.0) / 100.0) * (1.0 - black);
+
+    (channel(cyan), channel(magenta), channel(yellow))
+}
+
+// BT.709 luma coefficients for red and blue, with green making up the rest
+const KR: f32 = 0.2126;
+const KB: f32 = 0.0722;
+
+fn rgb_to_ycbcr(red: f32, green: f32, blue: f32) -> [f32; 3] {
+    let luma = KR * red + (1.0 - KR - KB) * green + KB * blue;
+    let cb = (blue - luma) / (2.0 * (1.0 - KB));
+    let cr = (red - luma) / (2.0 * (1.0 - KR));
+
+    // Studio range leaves headroom above and below the signal
+    [16.0 + 219.0 * luma, 128.0 + 224.0 * cb, 128.0 + 224.0 * cr]
+}
+
+fn ycbcr_to_rgb(luma: f32, cb: f32, cr: f32) -> (f32, f32, f32) {
+    let luma = (luma - 16.0) / 219.0;
+    let cb = (cb - 128.0) / 224.0;
+    let cr = (cr - 128.0) / 224.0;
+
+    let red = luma + 2.0 * (1.0 - KR) * cr;
+    let blue = luma + 2.0 * (1.0 - KB) * cb;
+    let green = (luma - KR * red - KB * blue) / (1.0 - KR - KB);
+
+    (red, green, blue)
+}
+
+type Matrix = [[f32; 3]; 3];
+
+const LINEAR_SRGB_TO_XYZ_D65: Matrix = [
+    [0.412_390_8, 0.357_584_33, 0.180_480_8],
+    [0.212_639, 0.715_168_7, 0.072_192_32],
+    [0.019_330_818, 0.119_194_78, 0.950_532_14],
+];
+
+const XYZ_D65_TO_LINEAR_SRGB: Matrix = [
+    [3.240_97, -1.537_383_2, -0.498_610_76],
+    [-0.969_243_65, 1.875_967_5, 0.041_555_06],
+    [0.055_630_08, -0.203_976_96, 1.056_971_5],
+];
+
+// Bradford chromatic adaptation between the D65 and D50 white points
+const D65_TO_D50: Matrix = [
+    [1.047_929_8, 0.022_946_793, -0.050_192_23],
+    [0.029_627_815, 0.990_434_5, -0.017_073_825],
+    [-0.009_243_058, 0.015_055_145, 0.752_131_65],
+];
+
+const D50_TO_D65: Matrix = [
+    [0.955_473_4, -0.023_098_537, 0.063_259_31],
+    [-0.028_369_706, 1.009_995_5, 0.021_041_399],
+    [0.012_314_002, -0.020_507_697, 1.330_366],
+];
+
+// The Bradford cone response matrix and its inverse
+const XYZ_TO_BRADFORD: Matrix = [
+    [0.895_1, 0.266_4, -0.161_4],
+    [-0.750_2, 1.713_5, 0.036_7],
+    [0.038_9, -0.068_5, 1.029_6],
+];
+
+const BRADFORD_TO_XYZ: Matrix = [
+    [0.986_992_9, -0.147_054_3, 0.159_962_7],
+    [0.432_305_3, 0.518_360_3, 0.049_291_2],
+    [-0.008_528_7, 0.040_042_8, 0.968_486_7],
+];
+
+/// Builds the Bradford matrix adapting XYZ from one white point to another, by scaling
+/// each cone response by the ratio between the two whites.
+fn bradford(from: Illuminant, to: Illuminant) -> Matrix {
+    let source = multiply(&XYZ_TO_BRADFORD, from.white());
+    let destination = multiply(&XYZ_TO_BRADFORD, to.white());
+    let scale = [0, 1, 2].map(|k| destination[k] / source[k]);
+
+    [0, 1, 2].map(|row| {
+        [0, 1, 2].map(|column| {
+            (0..3)
+                .map(|k| BRADFORD_TO_XYZ[row][k] * scale[k] * XYZ_TO_BRADFORD[k][column])
+                .sum()
+        })
+    })
+}
+
+const D50_WHITE: [f32; 3] = [0.964_295_7, 1.0, 0.825_104_6];
+const D65_WHITE: [f32; 3] = [0.950_455_9, 1.0, 1.089_057_8];
+
+fn multiply(matrix: &Matrix, [x, y, z]: [f32; 3]) -> [f32; 3] {
+    matrix.map(|[a, b, c]| a * x + b * y + c * z)
+}
+
+/// Removes the sRGB transfer function from a 0-1 component.
+fn srgb_to_linear(c: f32) -> f32 {
+    if c.abs() <= 0.04045 {
+        c / 12.92
+    } else {
+        c.signum() * ((c.abs() + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Applies the sRGB transfer function to a linear 0-1 component.
+fn linear_to_srgb(c: f32) -> f32 {
+    if c.abs() <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        c.signum() * (1.055 * c.abs().powf(1.0 / 2.4) - 0.055)
+    }
+}
+
+fn rgb_to_xyz_d65(red: f32, green: f32, blue: f32) -> [f32; 3] {
+    let linear = [red, green, blue].map(srgb_to_linear);
+
+    multiply(&LINEAR_SRGB_TO_XYZ_D65, linear)
+}
+
+fn rgb_to_xyz_d50(red: f32, green: f32, blue: f32) -> [f32; 3] {
+    multiply(&D65_TO_D50, rgb_to_xyz_d65(red, green, blue))
+}
+
+fn xyz_d65_to_rgb(xyz: [f32; 3]) -> (f32, f32, f32) {
+    let linear = multiply(&XYZ_D65_TO_LINEAR_SRGB, xyz);
+    let [red, green, blue] = linear.map(linear_to_srgb);
+
+    (red, green, blue)
+}
+
+fn xyz_d50_to_rgb(xyz: [f32; 3]) -> (f32, f32, f32) {
+    xyz_d65_to_rgb(multiply(&D50_TO_D65, xyz))
+}
+
+/// Krystek's rational approximation of the Planckian locus in CIE 1960 UCS,
+/// converted to xyY and then sRGB.
+fn kelvin_to_rgb(kelvin: f32) -> (f32, f32, f32) {
+    // Squaring large temperatures needs more precision than f32 has to spare
+    let t = kelvin as f64;
+    let u = (0.860_117_757 + 1.541_182_54e-4 * t + 1.286_412_12e-7 * t * t)
+        / (1.0 + 8.424_202_35e-4 * t + 7.081_451_63e-7 * t * t);
+    let v = (0.317_398_726 + 4.228_062_45e-5 * t + 4.204_816_91e-8 * t * t)
+        / (1.0 - 2.897_418_16e-5 * t + 1.614_560_53e-7 * t * t);
+
+    let x = 3.0 * u / (2.0 * u - 8.0 * v + 4.0);
+    let y = 2.0 * v / (2.0 * u - 8.0 * v + 4.0);
+    let xyz = [x / y, 1.0, (1.0 - x - y) / y].map(|c| c as f32);
+
+    let linear = multiply(&XYZ_D65_TO_LINEAR_SRGB, xyz).map(|c| c.max(0.0));
+    let brightest = linear.iter().copied().fold(0.0, f32::max);
+    let [red, green, blue] = linear.map(|c| linear_to_srgb(c / brightest));
+
+    (red, green, blue)
+}
+
+/// The CIE 1931 2° standard observer's colour matching functions, every 10nm
+/// from 380nm to 780nm.
+const CIE_1931_OBSERVER: [[f32; 3]; 41] = [
+    [0.001_368, 0.000_039, 0.006_45], // 380nm
+    [0.004_243, 0.000_12, 0.020_05],  // 390nm
+    [0.014_31, 0.000_396, 0.067_85],  // 400nm
+    [0.043_51, 0.001_21, 0.207_4],    // 410nm
+    [0.134_38, 0.004, 0.645_6],       // 420nm
+    [0.283_9, 0.011_6, 1.385_6],      // 430nm
+    [0.348_28, 0.023, 1.747_06],      // 440nm
+    [0.336_2, 0.038, 1.772_11],       // 450nm
+    [0.290_8, 0.06, 1.669_2],         // 460nm
+    [0.195_36, 0.090_98, 1.287_64],   // 470nm
+    [0.095_64, 0.139_02, 0.812_95],   // 480nm
+    [0.032_01, 0.208_02, 0.465_18],   // 490nm
+    [0.004_9, 0.323, 0.272],          // 500nm
+    [0.009_3, 0.503, 0.158_2],        // 510nm
+    [0.063_27, 0.71, 0.078_25],       // 520nm
+    [0.165_5, 0.862, 0.042_16],       // 530nm
+    [0.290_4, 0.954, 0.020_3],        // 540nm
+    [0.433_45, 0.994_95, 0.008_75],   // 550nm
+    [0.594_5, 0.995, 0.003_9],        // 560nm
+    [0.762_1, 0.952, 0.002_1],        // 570nm
+    [0.916_3, 0.87, 0.001_65],        // 580nm
+    [1.026_3, 0.757, 0.001_1],        // 590nm
+    [1.062_2, 0.631, 0.000_8],        // 600nm
+    [1.002_6, 0.503, 0.000_34],       // 610nm
+    [0.854_45, 0.381, 0.000_19],      // 620nm
+    [0.642_4, 0.265, 0.000_05],       // 630nm
+    [0.447_9, 0.175, 0.000_02],       // 640nm
+    [0.283_5, 0.107, 0.0],            // 650nm
+    [0.164_9, 0.061, 0.0],            // 660nm
+    [0.087_4, 0.032, 0.0],            // 670nm
+    [0.046_77, 0.017, 0.0],           // 680nm
+    [0.022_7, 0.008_21, 0.0],         // 690nm
+    [0.011_359, 0.004_102, 0.0],      // 700nm
+    [0.005_79, 0.002_091, 0.0],       // 710nm
+    [0.002_899, 0.001_047, 0.0],      // 720nm
+    [0.001_44, 0.000_52, 0.0],        // 730nm
+    [0.000_69, 0.000_249, 0.0],       // 740nm
+    [0.000_332, 0.000_12, 0.0],       // 750nm
+    [0.000_166, 0.000_06, 0.0],       // 760nm
+    [0.000_083, 0.000_03, 0.0],       // 770nm
+    [0.000_042, 0.000_015, 0.0],      // 780nm
+];
+
+/// The XYZ response to light of a single wavelength, interpolating between the
+/// tabulated colour matching functions.
+fn color_matching_functions(wavelength: f32) -> [f32; 3] {
+    let position = ((wavelength.clamp(380.0, 780.0) - 380.0) / 10.0).min(39.999);
+    let (i, t) = (position as usize, position.fract());
+    let (below, above) = (CIE_1931_OBSERVER[i], CIE_1931_OBSERVER[i + 1]);
+
+    [0, 1, 2].map(|c| below[c] + (above[c] - below[c]) * t)
+}
+
+fn wavelength_to_rgb(wavelength: f32) -> (f32, f32, f32) {
+    // Spectral colours lie outside sRGB, so clip them to its nearest edge
+    let linear = multiply(
+        &XYZ_D65_TO_LINEAR_SRGB,
+        color_matching_functions(wavelength),
+    )
+    .map(|c| c.max(0.0));
+    let brightest = linear.iter().copied().fold(0.0, f32::max);
+    let [red, green, blue] = linear.map(|c| linear_to_srgb(c / brightest));
+
+    (red, green, blue)
+}
+
+const LINEAR_P3_TO_XYZ_D65: Matrix = [
+    [0.486_570_95, 0.265_667_7, 0.198_217_29],
+    [0.228_974_56, 0.691_738_5, 0.079_286_91],
+    [0.0, 0.045_113_38, 1.043_944_4],
+];
+
+const XYZ_D65_TO_LINEAR_P3: Matrix = [
+    [2.493_497, -0.931_383_6, -0.402_710_8],
+    [-0.829_489, 1.762_664_1, 0.023_624_686],
+    [0.035_845_83, -0.076_172_39, 0.956_884_5],
+];
+
+const LINEAR_A98_TO_XYZ_D65: Matrix = [
+    [0.576_669_04, 0.185_558_24, 0.188_228_65],
+    [0.297_344_97, 0.627_363_57, 0.075_291_46],
+    [0.027_031_36, 0.070_688_85, 0.991_337_5],
+];
+
+const XYZ_D65_TO_LINEAR_A98: Matrix = [
+    [2.041_588, -0.565_007, -0.344_731_35],
+    [-0.969_243_65, 1.875_967_5, 0.041_555_06],
+    [0.013_444_28, -0.118_362_39, 1.015_175],
+];
+
+const LINEAR_PROPHOTO_TO_XYZ_D50: Matrix = [
+    [0.797_760_5, 0.135_185_84, 0.031_349_35],
+    [0.288_071_13, 0.711_843_2, 0.000_085_653_96],
+    [0.0, 0.0, 0.825_104_6],
+];
+
+const XYZ_D50_TO_LINEAR_PROPHOTO: Matrix = [
+    [1.345_799, -0.255_580_1, -0.051_106_285],
+    [-0.544_622_5, 1.508_232_7, 0.020_536_032],
+    [0.0, 0.0, 1.211_967_5],
+];
+
+const LINEAR_REC2020_TO_XYZ_D65: Matrix = [
+    [0.636_958_05, 0.144_616_9, 0.168_880_98],
+    [0.262_700_2, 0.677_998_1, 0.059_301_716],
+    [0.0, 0.028_072_693, 1.060_985_1],
+];
+
+const XYZ_D65_TO_LINEAR_REC2020: Matrix = [
+    [1.716_651_2, -0.355_670_8, -0.253_366_3],
+    [-0.666_684_3, 1.616_481_2, 0.015_768_546],
+    [0.017_639_857, -0.042_770_613, 0.942_103_1],
+];
+
+fn a98_to_linear(c: f32) -> f32 {
+    c.signum() * c.abs().powf(563.0 / 256.0)
+}
+
+fn linear_to_a98(c: f32) -> f32 {
+    c.signum() * c.abs().powf(256.0 / 563.0)
+}
+
+fn prophoto_to_linear(c: f32) -> f32 {
+    if c.abs() <= 16.0 / 512.0 {
+        c / 16.0
+    } else {
+        c.signum() * c.abs().powf(1.8)
+    }
+}
+
+fn linear_to_prophoto(c: f32) -> f32 {
+    if c.abs() >= 1.0 / 512.0 {
+        c.signum() * c.abs().powf(1.0 / 1.8)
+    } else {
+        c * 16.0
+    }
+}
+
+const REC2020_ALPHA: f32 = 1.099_296_8;
+const REC2020_BETA: f32 = 0.018_053_97;
+
+fn rec2020_to_linear(c: f32) -> f32 {
+    if c.abs() < REC2020_BETA * 4.5 {
+        c / 4.5
+    } else {
+        c.signum() * ((c.abs() + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45)
+    }
+}
+
+fn linear_to_rec2020(c: f32) -> f32 {
+    if c.abs() >= REC2020_BETA {
+        c.signum() * (REC2020_ALPHA * c.abs().powf(0.45) - (REC2020_ALPHA - 1.0))
+    } else {
+        c * 4.5
+    }
+}
+
+const LAB_EPSILON: f32 = 216.0 / 24389.0;
+const LAB_KAPPA: f32 = 24389.0 / 27.0;
+
+fn xyz_to_lab(xyz: [f32; 3], white: [f32; 3]) -> [f32; 3] {
+    let [x, y, z] = [0, 1, 2].map(|i| {
+        let c = xyz[i] / white[i];
+
+        if c > LAB_EPSILON {
+            c.cbrt()
+        } else {
+            (LAB_KAPPA * c + 16.0) / 116.0
+        }
+    });
+
+    [116.0 * y - 16.0, 500.0 * (x - y), 200.0 * (y - z)]
+}
+
+fn lab_to_xyz([lightness, a, b]: [f32; 3], white: [f32; 3]) -> [f32; 3] {
+    let fy = (lightness + 16.0) / 116.0;
+    let fx = a / 500.0 + fy;
+    let fz = fy - b / 200.0;
+
+    let x = if fx.powi(3) > LAB_EPSILON {
+        fx.powi(3)
+    } else {
+        (116.0 * fx - 16.0) / LAB_KAPPA
+    };
+    let y = if lightness > LAB_KAPPA * LAB_EPSILON {
+        fy.powi(3)
+    } else {
+        lightness / LAB_KAPPA
+    };
+    let z = if fz.powi(3) > LAB_EPSILON {
+        fz.powi(3)
+    } else {
+        (116.0 * fz - 16.0) / LAB_KAPPA
+    };
+
+    [x * white[0], y * white[1], z * white[2]]
+}
+
+fn lab_to_lch([lightness, a, b]: [f32; 3]) -> [f32; 3] {
+    let chroma = (a * a + b * b).sqrt();
+    let mut hue = b.atan2(a).to_degrees();
+
+    if hue < 0.0 {
+        hue += 360.0;
+    }
+
+    [lightness, chroma, hue]
+}
+
+fn lch_to_lab([lightness, chroma, hue]: [f32; 3]) -> [f32; 3] {
+    let hue = hue.to_radians();
+
+    [lightness, chroma * hue.cos(), chroma * hue.sin()]
+}
+
+const LINEAR_SRGB_TO_LMS: Matrix = [
+    [0.412_221_46, 0.536_332_55, 0.051_445_995],
+    [0.211_903_5, 0.680_699_5, 0.107_396_96],
+    [0.088_302_46, 0.281_718_85, 0.629_978_7],
+];
+
+const LMS_TO_OKLAB: Matrix = [
+    [0.210_454_26, 0.793_617_8, -0.004_072_047],
+    [1.977_998_5, -2.428_592_2, 0.450_593_7],
+    [0.025_904_037, 0.782_771_77, -0.808_675_77],
+];
+
+const OKLAB_TO_LMS: Matrix = [
+    [1.0, 0.396_337_78, 0.215_803_76],
+    [1.0, -0.105_561_346, -0.063_854_17],
+    [1.0, -0.089_484_18, -1.291_485_5],
+];
+
+const LMS_TO_LINEAR_SRGB: Matrix = [
+    [4.076_741_7, -3.307_711_6, 0.230_969_94],
+    [-1.268_438, 2.609_757_4, -0.341_319_38],
+    [-0.004_196_086_3, -0.703_418_6, 1.707_614_7],
+];
+
+fn linear_srgb_to_oklab(linear: [f32; 3]) -> [f32; 3] {
+    let lms = multiply(&LINEAR_SRGB_TO_LMS, linear).map(f32::cbrt);
+
+    multiply(&LMS_TO_OKLAB, lms)
+}
+
+fn oklab_to_linear_srgb(oklab: [f32; 3]) -> [f32; 3] {
+    let lms = multiply(&OKLAB_TO_LMS, oklab).map(|c| c.powi(3));
+
+    multiply(&LMS_TO_LINEAR_SRGB, lms)
+}
+
+// How far outside 0-1 a linear sRGB component can be and still count as in gamut,
+// to allow for floating point error
+const GAMUT_EPSILON: f32 = 0.000_1;
+
+fn in_srgb_gamut(linear: [f32; 3]) -> bool {
+    linear
+        .iter()
+        .all(|c| (-GAMUT_EPSILON..=1.0 + GAMUT_EPSILON).contains(c))
+}
+
+/// Brings linear sRGB into gamut with the CSS Color 4 algorithm: reduce the colour's
+/// OKLCh chroma until clipping what's left makes no noticeable difference.
+fn reduce_chroma_into_srgb(linear: [f32; 3]) -> [f32; 3] {
+    // The OKLab distance below which clipping isn't noticeable
+    const JND: f32 = 0.02;
+    let clip = |linear: [f32; 3]| linear.map(|c| c.clamp(0.0, 1.0));
+    let clipping_error = |linear: [f32; 3]| {
+        let [l1, a1, b1] = linear_srgb_to_oklab(linear);
+        let [l2, a2, b2] = linear_srgb_to_oklab(clip(linear));
+        ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+    };
+
+    let [lightness, chroma, hue] = lab_to_lch(linear_srgb_to_oklab(linear));
+    if lightness <= 0.0 || lightness >= 1.0 || clipping_error(linear) < JND {
+        return clip(linear);
+    }
+
+    // Binary search for the highest chroma that clips to within JND of itself
+    let (mut min, mut max) = (0.0, chroma);
+    let mut min_in_gamut = true;
+    let mut clipped = clip(linear);
+    while max - min > GAMUT_EPSILON {
+        let chroma = (min + max) / 2.0;
+        let current = oklab_to_linear_srgb(lch_to_lab([lightness, chroma, hue]));
+
+        if min_in_gamut && in_srgb_gamut(current) {
+            min = chroma;
+            continue;
+        }
+
+        clipped = clip(current);
+        let error = clipping_error(current);
+        if error < JND {
+            if JND - error < GAMUT_EPSILON {
+                break;
+            }
+            min_in_gamut = false;
+            min = chroma;
+        } else {
+            max = chroma;
+        }
+    }
+
+    clipped
+}
+
+/// The CIEDE2000 colour difference between two Lab colours, following Sharma,
+/// Wu and Dalal's implementation notes.
+fn ciede2000([l1, a1, b1]: [f32; 3], [l2, a2, b2]: [f32; 3]) -> f32 {
+    const POW_25_7: f32 = 6_103_515_625.0;
+    let hue_of = |a: f32, b: f32| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+
+    let c_bar = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g = 0.5 * (1.0 - (c_bar.powi(7) / (c_bar.powi(7) + POW_25_7)).sqrt());
+    let (a1, a2) = ((1.0 + g) * a1, (1.0 + g) * a2);
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let (h1, h2) = (hue_of(a1, b1), hue_of(a2, b2));
+
+    let delta_l = l2 - l1;
+    let delta_c = c2 - c1;
+    let delta_h = match h2 - h1 {
+        _ if c1 * c2 == 0.0 => 0.0,
+        d if d > 180.0 => d - 360.0,
+        d if d < -180.0 => d + 360.0,
+        d => d,
+    };
+    let delta_h = 2.0 * (c1 * c2).sqrt() * (delta_h / 2.0).to_radians().sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+    let h_bar = match (h1 + h2, (h1 - h2).abs()) {
+        (sum, _) if c1 * c2 == 0.0 => sum,
+        (sum, diff) if diff <= 180.0 => sum / 2.0,
+        (sum, _) if sum < 360.0 => (sum + 360.0) / 2.0,
+        (sum, _) => (sum - 360.0) / 2.0,
+    };
+
+    let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar).to_radians().cos()
+        + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+    let delta_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (c_bar.powi(7) / (c_bar.powi(7) + POW_25_7)).sqrt();
+    let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar;
+    let s_h = 1.0 + 0.015 * c_bar * t;
+    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let (l, c, h) = (delta_l / s_l, delta_c / s_c, delta_h / s_h);
+    (l * l + c * c + h * h + r_t * c * h).sqrt()
+}
+
+fn round_to_one_decimal_place(n: f32) -> f32 {
+    // Adding zero turns a negative zero into a positive one, so it isn't printed as `-0`
+    (n * 10.0).round() / 10.0 + 0.0
+}
+
+fn round_to_three_decimal_places(n: f32) -> f32 {
+    (n * 1000.0).round() / 1000.0 + 0.0
+}
+
+fn rgb_to_hex(red: f32, green: f32, blue: f32, alpha: f32) -> String {
+    if (alpha - 1.0).abs() < f32::EPSILON {
+        format!(
+            "#{:02X}{:02X}{:02X}",
+            red.round() as i64,
+            green.round() as i64,
+            blue.round() as i64
+        )
+    } else {
+        let alpha = (alpha * 255.0).round() as i64;
+        format!(
+            "#{:02X}{:02X}{:02X}{:02X}",
+            red.round() as i64,
+            green.round() as i64,
+            blue.round() as i64,
+            alpha
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn convert_rgb_to_hsl() {
+        let color = Color::from_rgb(23.0, 11.0, 33.0, 1.0);
+
+        assert_eq!((273.0, 50.0, 8.6, 1.0), color.hsl());
+    }
+
+    #[test]
+    fn convert_hsl_to_rgb() {
+        let color = Color::from_hsl(122.0, 33.0, 12.0, 0.4);
+
+        assert_eq!((21.0, 41.0, 21.0, 0.4), color.rgb());
+    }
+
+    #[test]
+    fn hsl_hue_wraps() {
+        assert_eq!(
+            Color::from_hsl(240.0, 100.0, 50.0, 1.0),
+            "hsl(-120deg 100% 50%)".parse().unwrap()
+        );
+        assert_eq!(
+            Color::from_hsl(120.0, 100.0, 50.0, 1.0),
+            "hsl(480deg 100% 50%)".parse().unwrap()
+        );
+        assert_eq!(
+            Color::from_hsl(180.0, 100.0, 50.0, 1.0),
+            "hsl(1.5turn 100% 50%)".parse().unwrap()
+        );
+        assert_eq!(
+            Color::from_hsl(360.0, 100.0, 50.0, 1.0).rgb(),
+            (255.0, 0.0, 0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn parse_lenient() {
+        let red = Color::from_rgb(255.0, 0.0, 0.0, 1.0);
+        for input in [
+            "ff0000",
+            "f00;",
+            "rgb 255 0 0",
+            "rgb(255, 0 0)",
+            "  rgb(255 0 0);  ",
+            "hsl 0, 100%, 50%",
+        ] {
+            assert_eq!(red, Color::from_str_lenient(input).unwrap(), "{}", input);
+        }
+
+        // Digits alone are hex, not the packed decimals the strict parser reads them as
+        for (input, hex) in [
+            ("336699", "#336699"),
+            ("123456", "#123456"),
+            ("010", "#001100"),
+        ] {
+            let color = Color::from_str_lenient(input).unwrap();
+            assert_eq!(hex, color.hex_string(), "{}", input);
+        }
+        assert_eq!("#05233B", "336699".parse::<Color>().unwrap().hex_string());
+
+        let color = Color::from_str_lenient("rgba(255 0 0 0.5)").unwrap();
+        assert_eq!(color.rgb(), (255.0, 0.0, 0.0, 0.5));
+        let color = Color::from_str_lenient("device-cmyk 0 1 1 0, 0.5").unwrap();
+        assert_eq!(color.rgb(), (255.0, 0.0, 0.0, 0.5));
+
+        // The strict parser is unchanged, and its error is kept
+        assert!("ff0000".parse::<Color>().is_err());
+        assert!("rgb 255 0 0".parse::<Color>().is_err());
+        assert!(matches!(
+            Color::from_str_lenient("rgb 255 0"),
+            Err(crate::Error::InvalidColor { column: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn convert_phonetic_codes() {
+        let color = Color::from_rgb(51.0, 102.0, 153.0, 0.5);
+        let code = color.to_phonetic();
+        assert_eq!("gato-koki", code);
+        let heard = Color::from_phonetic(&code).unwrap();
+        assert_eq!("#336699", heard.hex_string());
+        assert_eq!(ColorType::Hex, heard.color_type());
+
+        for name in ["tomato", "rebeccapurple", "black", "white"] {
+            let color = Color::from_name(name).unwrap();
+            assert_eq!(color, Color::from_phonetic(&color.to_phonetic()).unwrap());
+        }
+
+        assert_eq!(
+            "`kato-rim` is not a phonetic colour code",
+            Color::from_phonetic("kato-rim").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn convert_packed_integers() {
+        let color = Color::from_u32_rgb(0xFF8800);
+        assert_eq!((255.0, 136.0, 0.0, 1.0), color.rgb());
+        assert_eq!("#FF8800", color.to_string());
+        assert_eq!(color, Color::from_u32_rgb(0xABFF8800));
+
+        let color = Color::from_u32_rgba(0xFF880080);
+        assert_eq!((255.0, 136.0, 0.0, 128.0 / 255.0), color.rgb());
+    }
+
+    #[test]
+    fn convert_kelvin() {
+        let rgb = |kelvin| Color::from_kelvin(kelvin).rgb();
+
+        assert_eq!((255.0, 23.0, 0.0, 1.0), rgb(1000.0));
+        // A warm white incandescent bulb
+        assert_eq!((255.0, 173.0, 88.0, 1.0), rgb(2700.0));
+        assert_eq!((255.0, 248.0, 254.0, 1.0), rgb(6500.0));
+        assert_eq!((205.0, 217.0, 255.0, 1.0), rgb(10000.0));
+    }
+
+    #[test]
+    fn css_keywords() {
+        let color: Color = "transparent".parse().unwrap();
+        assert_eq!((0.0, 0.0, 0.0, 0.0), color.rgb());
+
+        let color: Color = "currentColor".parse().unwrap();
+        assert!(color.is_current_color());
+        assert_eq!("currentcolor", color.to_string());
+        assert_eq!("#000000", color.hex_string());
+
+        let tomato: Color = "tomato".parse().unwrap();
+        let color = color.resolve_current_color(&tomato);
+        assert!(!color.is_current_color());
+        assert_eq!("tomato", color.to_string());
+        assert_eq!(
+            tomato
+                .clone()
+                .resolve_current_color(&Color::current_color()),
+            tomato
+        );
+    }
+
+    #[test]
+    fn ciede2000_reference_pairs() {
+        // From Sharma, Wu and Dalal's test data
+        let pairs = [
+            ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+            ([50.0, -1.3802, -84.2814], [50.0, 0.0, -82.7485], 1.0),
+            ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+            ([50.0, 2.49, -0.001], [50.0, -2.49, 0.0011], 7.2195),
+            (
+                [60.2574, -34.0099, 36.2677],
+                [60.4626, -34.1751, 39.4387],
+                1.2644,
+            ),
+            ([2.0776, 0.0795, -1.135], [0.9033, -0.0636, -0.5514], 0.9082),
+        ];
+        for (lab1, lab2, expected) in pairs {
+            let delta_e = ciede2000(lab1, lab2);
+            assert!(
+                (delta_e - expected).abs() < 0.001,
+                "{} != {}",
+                delta_e,
+                expected
+            );
+        }
+
+        let color = Color::from_rgb(10.0, 20.0, 30.0, 1.0);
+        assert_eq!(color.delta_e(&color), 0.0);
+    }
+
+    #[test]
+    fn beautify() {
+        // Close to hsl(210 50% 40%)
+        let color = Color::from_rgb(50.0, 101.0, 153.0, 0.5).with_type(ColorType::Hex);
+        let pretty = color.beautify();
+        assert_eq!(pretty, Color::from_hsl(210.0, 50.0, 40.0, 0.5));
+        assert_eq!(pretty.color_type(), ColorType::Hex);
+        assert!(color.delta_e(&pretty) <= BEAUTIFY_MAX_DELTA_E);
+
+        // Nothing round is close enough
+        assert_eq!(color.beautify_within(0.1), color);
+    }
+
+    #[test]
+    fn convert_ycbcr() {
+        let red = Color::from_rgb(255.0, 0.0, 0.0, 1.0);
+        assert_eq!((62.6, 102.3, 240.0, 1.0), red.ycbcr());
+        assert_eq!("ycbcr(62.6 102.3 240)", red.ycbcr_string());
+
+        let white = Color::from_rgb(255.0, 255.0, 255.0, 1.0);
+        assert_eq!((235.0, 128.0, 128.0, 1.0), white.ycbcr());
+        let black = Color::from_rgb(0.0, 0.0, 0.0, 1.0);
+        assert_eq!((16.0, 128.0, 128.0, 1.0), black.ycbcr());
+
+        for rgb in [(255.0, 0.0, 0.0), (12.0, 200.0, 99.0), (240.0, 240.0, 10.0)] {
+            let color = Color::from_rgb(rgb.0, rgb.1, rgb.2, 1.0);
+            let (luma, cb, cr, alpha) = color.ycbcr();
+            assert_eq!(color.rgb(), Color::from_ycbcr(luma, cb, cr, alpha).rgb());
+        }
+    }
+
+    #[test]
+    fn convert_wavelength() {
+        assert_eq!(
+            (114.0, 0.0, 255.0, 1.0),
+            Color::from_wavelength(380.0).rgb()
+        );
+        assert_eq!((0.0, 45.0, 255.0, 1.0), Color::from_wavelength(470.0).rgb());
+        assert_eq!((0.0, 255.0, 0.0, 1.0), Color::from_wavelength(550.0).rgb());
+        assert_eq!(
+            (255.0, 180.0, 0.0, 1.0),
+            Color::from_wavelength(580.0).rgb()
+        );
+        assert_eq!((255.0, 0.0, 0.0, 1.0), Color::from_wavelength(700.0).rgb());
+
+        // Hue runs from violet to red through the spectrum, always at full brightness
+        let mut last_hue = 360.0;
+        for nm in [
+            380.0, 450.0, 470.0, 500.0, 550.0, 580.0, 600.0, 650.0, 700.0, 780.0,
+        ] {
+            let color = Color::from_wavelength(nm);
+            let (hue, _, _, _) = color.hsl();
+            let (_, _, value, _) = color.hsv();
+            assert!(hue <= last_hue, "{}nm", nm);
+            assert_eq!(100.0, value, "{}nm", nm);
+            last_hue = hue;
+        }
+    }
+
+    #[test]
+    fn apply_lut() {
+        // Swaps red and blue
+        let lut = crate::lut::Lut3d::from_cube(
+            "LUT_3D_SIZE 2\n\
+             0 0 0\n0 0 1\n0 1 0\n0 1 1\n1 0 0\n1 0 1\n1 1 0\n1 1 1\n",
+        )
+        .unwrap();
+        let color: Color = "rgb(255 102 0 / 0.5)".parse().unwrap();
+
+        let graded = color.apply_lut(&lut);
+        assert_eq!((0.0, 102.0, 255.0, 0.5), graded.rgb());
+        assert_eq!(ColorType::Rgb, graded.color_type());
+    }
+
+    #[test]
+    fn convert_hex_to_rgb() {
+        let color = Color::from_hex("#ff0033").unwrap();
+        assert_eq!((255.0, 0.0, 51.0, 1.0), color.rgb());
+
+        let color = Color::from_hex("f03").unwrap();
+        assert_eq!((255.0, 0.0, 51.0, 1.0), color.rgb());
+        assert_eq!("#FF0033", color.to_string());
+
+        let color = Color::from_hex("#FF003380").unwrap();
+        assert_eq!("#FF003380", color.to_string());
+
+        assert!(Color::from_hex("#ff00").is_ok());
+        assert!(Color::from_hex("#ff0").is_ok());
+        assert!(Color::from_hex("#ff00zz").is_err());
+        assert!(Color::from_hex("#ff00330").is_err());
+    }
+
+    #[test]
+    fn convert_name_to_rgb() {
+        let color = Color::from_name("rebeccapurple").unwrap();
+        assert_eq!((102.0, 51.0, 153.0, 1.0), color.rgb());
+        assert_eq!(color, "rebeccapurple".parse().unwrap());
+
+        let color = Color::from_name("transparent").unwrap();
+        assert_eq!((0.0, 0.0, 0.0, 0.0), color.rgb());
+
+        assert!(Color::from_name("notacolor").is_err());
+    }
+
+    #[test]
+    fn conversions_keep_precision() {
+        let mut color = Color::from_hsl(120.0, 50.0, 50.0, 1.0);
+        for _ in 0..10 {
+            let (hue, whiteness, blackness, alpha) = color.hwb();
+            let (hue, saturation, value, alpha) =
+                Color::from_hwb(hue, whiteness, blackness, alpha).hsv();
+            let (hue, saturation, luminosity, alpha) =
+                Color::from_hsv(hue, saturation, value, alpha).hsl();
+            color = Color::from_hsl(hue, saturation, luminosity, alpha);
+        }
+
+        assert_eq!((120.0, 50.0, 50.0, 1.0), color.hsl());
+        // RGB is rounded for display, but not in storage
+        assert_eq!((64.0, 191.0, 64.0, 1.0), color.rgb());
+        assert_eq!("hsl(120 50 50)", color.to_string());
+    }
+
+    #[test]
+    fn convert_hwb_to_rgb() {
+        let color = Color::from_hwb(120.0, 30.0, 40.0, 1.0);
+        assert_eq!((77.0, 153.0, 77.0, 1.0), color.rgb());
+        assert_eq!((120.0, 30.0, 40.0, 1.0), color.hwb());
+
+        let color = Color::from_hwb(0.0, 60.0, 60.0, 0.5);
+        assert_eq!((128.0, 128.0, 128.0, 0.5), color.rgb());
+    }
+
+    #[test]
+    fn convert_rgb_to_hwb() {
+        let color = Color::from_rgb(255.0, 0.0, 51.0, 1.0);
+        assert_eq!((348.0, 0.0, 0.0, 1.0), color.hwb());
+        assert_eq!("hwb(348 0 0)", color.hwb_string());
+    }
+
+    #[test]
+    fn convert_rgb_to_office() {
+        let color = Color::from_rgb(51.0, 102.0, 153.0, 0.5);
+        assert_eq!("10053171", color.office_string());
+        assert_eq!("RGB(51, 102, 153)", color.vba_string());
+    }
+
+    #[test]
+    fn convert_rgb_to_lab() {
+        let color = Color::from_rgb(255.0, 0.0, 0.0, 1.0);
+        assert_eq!((54.3, 80.8, 69.9, 1.0), color.lab());
+        assert_eq!((54.3, 106.8, 40.9, 1.0), color.lch());
+
+        let color = Color::from_rgb(255.0, 255.0, 255.0, 0.5);
+        assert_eq!((100.0, 0.0, 0.0, 0.5), color.lab());
+        assert_eq!("lab(100 0 0 / 0.5)", color.lab_string());
+    }
+
+    #[test]
+    fn convert_lab_to_rgb() {
+        let color = Color::from_lab(54.29, 80.8, 69.89, 1.0);
+        assert_eq!((255.0, 0.0, 0.0, 1.0), color.rgb());
+
+        let color = Color::from_lch(54.29, 106.84, 40.85, 1.0);
+        assert_eq!((255.0, 0.0, 0.0, 1.0), color.rgb());
+
+        let color = Color::from_lab(0.0, 0.0, 0.0, 1.0);
+        assert_eq!((0.0, 0.0, 0.0, 1.0), color.rgb());
+    }
+
+    #[test]
+    fn convert_lab_d65() {
+        let color = Color::from_rgb(255.0, 0.0, 0.0, 1.0);
+        assert_eq!((53.2, 80.1, 67.2, 1.0), color.lab_d65());
+        assert_eq!((53.2, 104.6, 40.0, 1.0), color.lch_d65());
+
+        // White maps to the reference white
+        let white = Color::from_rgb(255.0, 255.0, 255.0, 1.0);
+        assert_eq!((100.0, 0.0, 0.0, 1.0), white.lab_d65());
+
+        let color = Color::from_lab_d65(53.24, 80.09, 67.2, 0.5);
+        assert_eq!((255.0, 0.0, 0.0, 0.5), color.rgb());
+        assert_eq!(ColorType::Lab, color.color_type());
+        let color = Color::from_lch_d65(53.24, 104.55, 40.0, 1.0);
+        assert_eq!((255.0, 0.0, 0.0, 1.0), color.rgb());
+        assert_eq!("lch(54.3 106.8 40.9)", color.to_string());
+    }
+
+    #[test]
+    fn gamut_mapping() {
+        assert!(ColorSpace::DisplayP3.in_srgb_gamut([0.5, 0.5, 0.5]));
+        assert!(ColorSpace::Srgb.in_srgb_gamut([1.0, 0.0, 0.0]));
+        assert!(!ColorSpace::DisplayP3.in_srgb_gamut([1.0, 0.0, 0.0]));
+        assert!(!ColorSpace::Rec2020.in_srgb_gamut([0.0, 1.0, 0.0]));
+
+        let map = |space, [c1, c2, c3]: [f32; 3], mapping| {
+            Color::from_space_mapped(space, c1, c2, c3, 1.0, mapping)
+        };
+
+        // Clipping shifts the hue of Display P3 red (28.96°) and Rec.2020 green (151.4°)
+        let red = [1.0, 0.0, 0.0];
+        let clipped = map(ColorSpace::DisplayP3, red, GamutMapping::Clip);
+        let mapped = map(ColorSpace::DisplayP3, red, GamutMapping::ReduceChroma);
+        assert_eq!("#FF0000", clipped.hex_string());
+        assert_eq!(29.2, clipped.oklch().2);
+        assert_eq!(28.9, mapped.oklch().2);
+        assert!(mapped.in_srgb_gamut());
+        assert_eq!(ColorType::Color(ColorSpace::DisplayP3), mapped.color_type());
+
+        let green = [0.0, 1.0, 0.0];
+        let clipped = map(ColorSpace::Rec2020, green, GamutMapping::Clip);
+        let mapped = map(ColorSpace::Rec2020, green, GamutMapping::ReduceChroma);
+        assert_eq!(142.5, clipped.oklch().2);
+        assert_eq!(150.0, mapped.oklch().2);
+
+        // Colours already in gamut are left alone
+        let grey = [0.5, 0.5, 0.5];
+        assert_eq!(
+            map(ColorSpace::DisplayP3, grey, GamutMapping::Clip),
+            map(ColorSpace::DisplayP3, grey, GamutMapping::ReduceChroma)
+        );
+    }
+
+    #[test]
+    fn convert_linear_rgb() {
+        let color = Color::from_rgb(255.0, 128.0, 0.0, 0.5);
+        let (red, green, blue, alpha) = color.linear_rgb();
+        assert_eq!((1.0, 0.0, 0.5), (red, blue, alpha));
+        // The sRGB curve, not a plain 2.2 power
+        assert!((green - 0.215_861).abs() < 1e-6, "{}", green);
+
+        let color = Color::from_linear_rgb(red, green, blue, alpha);
+        assert_eq!((255.0, 128.0, 0.0, 0.5), color.rgb());
+        assert_eq!("color(srgb-linear 1 0.216 0 / 0.5)", color.to_string());
+
+        // Averaging black and white in linear light gives a lighter grey than 50%
+        let (white, _, _, _) = Color::from_rgb(255.0, 255.0, 255.0, 1.0).linear_rgb();
+        let (black, _, _, _) = Color::from_rgb(0.0, 0.0, 0.0, 1.0).linear_rgb();
+        let grey = (white + black) / 2.0;
+        assert_eq!(
+            (188.0, 188.0, 188.0, 1.0),
+            Color::from_linear_rgb(grey, grey, grey, 1.0).rgb()
+        );
+    }
+
+    #[test]
+    fn convert_xyz() {
+        let rounded = |(x, y, z, alpha): (f32, f32, f32, f32)| {
+            let [x, y, z] = [x, y, z].map(round_to_three_decimal_places);
+            (x, y, z, alpha)
+        };
+
+        let white = Color::from_rgb(255.0, 255.0, 255.0, 1.0);
+        assert_eq!((0.95, 1.0, 1.089, 1.0), rounded(white.xyz(Illuminant::D65)));
+        assert_eq!(
+            (0.964, 1.0, 0.825, 1.0),
+            rounded(white.xyz(Illuminant::D50))
+        );
+
+        let red = Color::from_rgb(255.0, 0.0, 0.0, 0.5);
+        assert_eq!(
+            (0.412, 0.213, 0.019, 0.5),
+            rounded(red.xyz(Illuminant::D65))
+        );
+        assert_eq!(
+            (0.436, 0.222, 0.014, 0.5),
+            rounded(red.xyz(Illuminant::D50))
+        );
+
+        for illuminant in [Illuminant::D50, Illuminant::D65] {
+            let (x, y, z, alpha) = red.xyz(illuminant);
+            let color = Color::from_xyz(illuminant, x, y, z, alpha);
+            assert_eq!(red.rgb(), color.rgb());
+        }
+        assert_eq!(
+            ColorType::Color(ColorSpace::XyzD50),
+            Color::from_xyz(Illuminant::D50, 0.5, 0.5, 0.5, 1.0).color_type()
+        );
+    }
+
+    #[test]
+    fn chromatic_adaptation() {
+        // The precomputed matrices from CSS agree with the ones built from the white
+        // points, give or take the rounding of the whites' chromaticities
+        for (computed, precomputed) in [
+            (bradford(Illuminant::D65, Illuminant::D50), D65_TO_D50),
+            (bradford(Illuminant::D50, Illuminant::D65), D50_TO_D65),
+        ] {
+            for (a, b) in computed.iter().flatten().zip(precomputed.iter().flatten()) {
+                assert!((a - b).abs() < 0.001, "{} != {}", a, b);
+            }
+        }
+
+        // A D50 grey, taken as D65 XYZ without adaptation, looks yellowish
+        let [x, y, z] = D50_WHITE.map(|c| c * 0.8);
+        let unadapted = Color::from_xyz(Illuminant::D65, x, y, z, 0.5);
+        assert_eq!("#F8E5C880", unadapted.hex_string());
+        let adapted = unadapted.adapt(Illuminant::D50, Illuminant::D65);
+        assert_eq!("#E7E7E780", adapted.hex_string());
+        assert_eq!(unadapted.color_type(), adapted.color_type());
+
+        let steelblue = Color::from_rgb(70.0, 130.0, 180.0, 1.0);
+        assert_eq!(
+            steelblue.rgb(),
+            steelblue.adapt(Illuminant::D65, Illuminant::D65).rgb()
+        );
+        let round_trip = steelblue
+            .adapt(Illuminant::D65, Illuminant::D50)
+            .adapt(Illuminant::D50, Illuminant::D65);
+        assert_eq!(steelblue.rgb(), round_trip.rgb());
+
+        // Wide gamut colours aren't clipped to sRGB on the way
+        let p3_red = Color::from_space(ColorSpace::DisplayP3, 1.0, 0.0, 0.0, 1.0);
+        let adapted = p3_red.adapt(Illuminant::D65, Illuminant::D50);
+        assert!(!adapted.in_srgb_gamut());
+        assert_eq!(p3_red.color_type(), adapted.color_type());
+        let round_trip = adapted.adapt(Illuminant::D50, Illuminant::D65);
+        assert_eq!("color(display-p3 1 0 0)", round_trip.to_string());
+        assert_eq!(p3_red.oklch(), round_trip.oklch());
+    }
+
+    #[test]
+    fn convert_rgb_to_latex() {
+        let color = Color::from_rgb(51.0, 102.0, 153.0, 0.5);
+        assert_eq!(
+            "\\definecolor{brand}{RGB}{51,102,153}",
+            color.latex_string("brand")
+        );
+        assert_eq!(
+            "\\definecolor{brand}{HTML}{336699}",
+            color.latex_html_string("brand")
+        );
+    }
+
+    #[test]
+    fn convert_rgb_to_oklab() {
+        let color = Color::from_rgb(255.0, 0.0, 0.0, 1.0);
+        assert_eq!((0.628, 0.225, 0.126, 1.0), color.oklab());
+        assert_eq!((0.628, 0.258, 29.2, 1.0), color.oklch());
+        assert_eq!("oklch(0.628 0.258 29.2)", color.oklch_string());
+
+        let color = Color::from_rgb(255.0, 255.0, 255.0, 1.0);
+        assert_eq!((1.0, 0.0, 0.0, 1.0), color.oklab());
+    }
+
+    #[test]
+    fn convert_oklab_to_rgb() {
+        let color = Color::from_oklab(0.628, 0.225, 0.126, 1.0);
+        assert_eq!((255.0, 0.0, 0.0, 1.0), color.rgb());
+
+        let color = Color::from_oklch(0.628, 0.258, 29.23, 0.5);
+        assert_eq!((255.0, 0.0, 0.0, 0.5), color.rgb());
+    }
+
+    #[test]
+    fn convert_rgb_to_typst_and_troff() {
+        let color = Color::from_rgb(51.0, 102.0, 153.0, 1.0);
+        assert_eq!("rgb(\"#336699\")", color.typst_string());
+        assert_eq!(".defcolor brand rgb #336699", color.troff_string("brand"));
+
+        let color = Color::from_rgb(51.0, 102.0, 153.0, 0.0);
+        assert_eq!("rgb(\"#33669900\")", color.typst_string());
+        assert_eq!(".defcolor brand rgb #336699", color.troff_string("brand"));
+    }
+
+    #[test]
+    fn convert_color_space_to_rgb() {
+        let color = Color::from_space(ColorSpace::Srgb, 1.0, 0.0, 0.2, 1.0);
+        assert_eq!((255.0, 0.0, 51.0, 1.0), color.rgb());
+
+        let color = Color::from_space(ColorSpace::SrgbLinear, 0.5, 0.5, 0.5, 1.0);
+        assert_eq!((188.0, 188.0, 188.0, 1.0), color.rgb());
+
+        let color = Color::from_space(ColorSpace::DisplayP3, 0.918, 0.2, 0.139, 1.0);
+        assert_eq!((255.0, 0.0, 0.0, 1.0), color.rgb());
+
+        // Outside of sRGB, so clamped
+        let color = Color::from_space(ColorSpace::DisplayP3, 1.0, 0.0, 0.0, 1.0);
+        assert_eq!((255.0, 0.0, 0.0, 1.0), color.rgb());
+
+        let color = Color::from_space(ColorSpace::XyzD65, 0.9505, 1.0, 1.089, 1.0);
+        assert_eq!((255.0, 255.0, 255.0, 1.0), color.rgb());
+    }
+
+    #[test]
+    fn convert_rgb_to_color_space() {
+        let color = Color::from_rgb(255.0, 0.0, 0.0, 1.0);
+        assert_eq!(
+            (0.917, 0.2, 0.139, 1.0),
+            color.in_space(ColorSpace::DisplayP3)
+        );
+        assert_eq!((0.859, 0.0, 0.0, 1.0), color.in_space(ColorSpace::A98Rgb));
+        assert_eq!(
+            (0.702, 0.276, 0.104, 1.0),
+            color.in_space(ColorSpace::ProphotoRgb)
+        );
+        assert_eq!(
+            (0.792, 0.231, 0.074, 1.0),
+            color.in_space(ColorSpace::Rec2020)
+        );
+        assert_eq!(
+            (0.412, 0.213, 0.019, 1.0),
+            color.in_space(ColorSpace::XyzD65)
+        );
+        assert_eq!(
+            "color(display-p3 0.917 0.2 0.139)",
+            color.color_string(ColorSpace::DisplayP3)
+        );
+
+        let white = Color::from_rgb(255.0, 255.0, 255.0, 1.0);
+        for space in [
+            ColorSpace::Srgb,
+            ColorSpace::SrgbLinear,
+            ColorSpace::DisplayP3,
+            ColorSpace::A98Rgb,
+            ColorSpace::ProphotoRgb,
+            ColorSpace::Rec2020,
+        ] {
+            assert_eq!((1.0, 1.0, 1.0, 1.0), white.in_space(space));
+        }
+    }
+
+    #[test]
+    fn parse_as_color_type() {
+        let color = Color::parse_as("010", ColorType::Hex).unwrap();
+        assert_eq!((0.0, 17.0, 0.0, 1.0), color.rgb());
+
+        let color = Color::parse_as("tomato", ColorType::Name).unwrap();
+        assert_eq!("tomato", color.to_string());
+
+        let error = Color::parse_as("010", ColorType::Name).unwrap_err();
+        assert_eq!("`010` is not a valid name colour", error.to_string());
+    }
+
+    #[test]
+    fn convert_cmyk_to_rgb() {
+        let color = Color::from_cmyk(0.0, 81.0, 81.0, 30.0, 1.0);
+        assert_eq!((179.0, 34.0, 34.0, 1.0), color.rgb());
+
+        let color = Color::from_cmyk(100.0, 100.0, 100.0, 100.0, 0.5);
+        assert_eq!((0.0, 0.0, 0.0, 0.5), color.rgb());
+    }
+
+    #[test]
+    fn convert_rgb_to_cmyk() {
+        let color = Color::from_rgb(179.0, 34.0, 34.0, 1.0);
+        assert_eq!((0.0, 81.0, 81.0, 29.8), color.cmyk());
+        assert_eq!("device-cmyk(0% 81% 81% 29.8%)", color.cmyk_string());
+
+        let color = Color::from_rgb(0.0, 0.0, 0.0, 0.5);
+        assert_eq!((0.0, 0.0, 0.0, 100.0), color.cmyk());
+        assert_eq!("device-cmyk(0% 0% 0% 100% / 0.5)", color.cmyk_string());
+    }
+
+    #[test]
+    fn convert_hsv_to_rgb() {
+        let color = Color::from_hsv(210.0, 66.7, 60.0, 1.0);
+        assert_eq!((51.0, 102.0, 153.0, 1.0), color.rgb());
+
+        let color = Color::from_hsv(0.0, 0.0, 100.0, 0.5);
+        assert_eq!((255.0, 255.0, 255.0, 0.5), color.rgb());
+
+        let color = Color::from_hsv(120.0, 100.0, 0.0, 1.0);
+        assert_eq!((0.0, 0.0, 0.0, 1.0), color.rgb());
+    }
+
+    #[test]
+    fn convert_rgb_to_hsv() {
+        let color = Color::from_rgb(51.0, 102.0, 153.0, 1.0);
+        assert_eq!((210.0, 66.7, 60.0, 1.0), color.hsv());
+        assert_eq!("hsv(210 66.7 60)", color.hsv_string());
+
+        let color = Color::from_rgb(0.0, 0.0, 0.0, 1.0);
+        assert_eq!((0.0, 0.0, 0.0, 1.0), color.hsv());
+    }
+
+    #[test]
+    fn suggest_similar_names() {
+        let error = Color::from_str("turqoise").unwrap_err();
+        assert_eq!(
+            "Unknown color name `turqoise`. Did you mean `turquoise`?",
+            error.to_string()
+        );
+
+        let error = Color::from_str("qwertyuiop").unwrap_err();
+        assert_eq!("Unknown color name `qwertyuiop`", error.to_string());
+
+        let error = ColorType::from_str("oklhc").unwrap_err();
+        assert!(error.to_string().ends_with("Did you mean `oklch`?"));
+        for (name, _) in COLOR_TYPE_NAMES {
+            assert!(
+                error.to_string().contains(&format!("`{}`", name)),
+                "{}",
+                name
+            );
+        }
+
+        let error = ColorType::from_str("display-p4").unwrap_err();
+        assert!(error.to_string().ends_with("Did you mean `display-p3`?"));
+    }
+
+    #[test]
+    fn missing_components() {
+        let color = Color::from_hsl(0.0, 50.0, 50.0, 1.0).with_missing(&[true]);
+        assert_eq!((191.0, 64.0, 64.0, 1.0), color.rgb());
+        assert_eq!("hsl(none 50 50)", color.to_string());
+        assert_eq!("rgb(191 64 64)", color.rgb_string());
+
+        let color = Color::from_rgb(255.0, 0.0, 0.0, 0.0).with_missing(&[false, true, false, true]);
+        assert_eq!((255.0, 0.0, 0.0, 0.0), color.rgb());
+        assert_eq!("rgb(255 none 0 / none)", color.to_string());
+        assert_eq!("#FF000000", color.hex_string());
+
+        let color = Color::from_oklch(0.5, 0.0, 0.0, 1.0).with_missing(&[false, false, true]);
+        assert_eq!("oklch(0.5 0 none)", color.to_string());
+
+        let color = Color::from_lch_d65(50.0, 0.0, 0.0, 1.0).with_missing(&[false, false, true]);
+        assert_eq!("lch(50 0 none)", color.to_string());
+
+        let color = Color::from_cmyk(0.0, 50.0, 0.0, 0.0, 0.0)
+            .with_missing(&[true, false, false, false, true]);
+        assert_eq!("device-cmyk(none 50% 0% 0% / none)", color.to_string());
+        // Missing components only apply to the notation they were given in
+        assert_eq!("hsl(300 100 75 / 0)", color.hsl_string());
+    }
+
+    #[test]
+    fn non_finite_components_are_zero() {
+        let nan = f32::NAN;
+        let inf = f32::INFINITY;
+        for (color, zeroed) in [
+            (
+                Color::from_rgb(nan, 0.0, 0.0, 1.0),
+                Color::from_rgb(0.0, 0.0, 0.0, 1.0),
+            ),
+            (
+                Color::from_hsl(120.0, nan, 50.0, nan),
+                Color::from_hsl(120.0, 0.0, 50.0, 0.0),
+            ),
+            (
+                Color::from_lab_d65(50.0, nan, 20.0, 1.0),
+                Color::from_lab_d65(50.0, 0.0, 20.0, 1.0),
+            ),
+            (
+                Color::from_lch_d65(50.0, 30.0, nan, 1.0),
+                Color::from_lch_d65(50.0, 30.0, 0.0, 1.0),
+            ),
+            (
+                Color::from_cmyk(nan, 0.0, 0.0, 0.0, 1.0),
+                Color::from_cmyk(0.0, 0.0, 0.0, 0.0, 1.0),
+            ),
+            (
+                Color::from_space(ColorSpace::DisplayP3, 1.0, nan, 0.0, 1.0),
+                Color::from_space(ColorSpace::DisplayP3, 1.0, 0.0, 0.0, 1.0),
+            ),
+            (
+                Color::from_space(ColorSpace::DisplayP3, inf, 0.0, 0.0, 1.0),
+                Color::from_space(ColorSpace::DisplayP3, 0.0, 0.0, 0.0, 1.0),
+            ),
+            (
+                Color::from_oklch(0.7, 0.1, -inf, 1.0),
+                Color::from_oklch(0.7, 0.1, 0.0, 1.0),
+            ),
+            (
+                Color::from_rgb(255.0, inf, 0.0, inf),
+                Color::from_rgb(255.0, 0.0, 0.0, 0.0),
+            ),
+        ] {
+            assert_eq!(zeroed, color);
+            assert_eq!(zeroed.to_string(), color.to_string());
+        }
     }
 }
@@ -0,0 +1,54 @@
+use crate::colors::Color;
+
+/// The coloured square emoji, with the colours Twemoji draws them in.
+const SQUARES: [(&str, [u8; 3]); 9] = [
+    ("🟥", [221, 46, 68]),
+    ("🟧", [244, 144, 12]),
+    ("🟨", [253, 203, 88]),
+    ("🟩", [120, 177, 89]),
+    ("🟦", [85, 172, 238]),
+    ("🟪", [170, 142, 214]),
+    ("🟫", [193, 105, 79]),
+    ("⬛", [49, 55, 61]),
+    ("⬜", [230, 231, 232]),
+];
+
+/// The square emoji that looks most like `color`. Distances are measured in OKLab
+/// with lightness given half weight, since the emoji are all fairly light and
+/// matching hue matters more than matching brightness.
+pub(crate) fn nearest(color: &Color) -> &'static str {
+    let (lightness, a, b, _) = color.oklab();
+
+    SQUARES
+        .iter()
+        .map(|(emoji, [red, green, blue])| {
+            let swatch = Color::from_rgb(*red as f32, *green as f32, *blue as f32, 1.0);
+            let (l2, a2, b2, _) = swatch.oklab();
+            let distance = ((lightness - l2) / 2.0).powi(2) + (a - a2).powi(2) + (b - b2).powi(2);
+            (distance, *emoji)
+        })
+        .min_by(|(d1, _), (d2, _)| d1.total_cmp(d2))
+        .map(|(_, emoji)| emoji)
+        .expect("there are emoji to choose from")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nearest_square() {
+        let nearest = |input: &str| nearest(&input.parse().unwrap());
+
+        assert_eq!(nearest("red"), "🟥");
+        assert_eq!(nearest("tomato"), "🟥");
+        assert_eq!(nearest("orange"), "🟧");
+        assert_eq!(nearest("gold"), "🟨");
+        assert_eq!(nearest("darkgreen"), "🟩");
+        assert_eq!(nearest("#336699"), "🟦");
+        assert_eq!(nearest("purple"), "🟪");
+        assert_eq!(nearest("chocolate"), "🟫");
+        assert_eq!(nearest("navy"), "⬛");
+        assert_eq!(nearest("white"), "⬜");
+    }
+}
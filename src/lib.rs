@@ -1,10 +1,57 @@
+mod ansi;
 pub mod colors;
+mod emoji;
+pub mod lut;
+mod names;
+#[cfg(feature = "ops")]
+mod ops;
 mod parse;
+mod phonetic;
+#[cfg(feature = "serde")]
+pub mod serialize;
+mod suggest;
+
+pub use parse::find_colors;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("Could not parse colour")]
-    InvalidColor,
-    #[error("Invalid color type `{0}` valid colors are: `hex`, `rgb`, `rgba`, `hsla`")]
-    InvalidColorType(String),
+    #[error(
+        "Could not parse colour: expected {} at column {column}, found {found}",
+        parse::one_of(.expected)
+    )]
+    InvalidColor {
+        input: String,
+        /// Byte offset into `input` where parsing failed
+        offset: usize,
+        /// The same position as a 1-based character column, for display
+        column: usize,
+        /// What was there instead, e.g. `` `)` `` or `the end of the input`
+        found: String,
+        expected: Vec<String>,
+    },
+    #[error(
+        "Invalid color type `{name}`, valid colors are: {} or a `color()` space such as `display-p3`{}",
+        colors::color_type_names(),
+        suggest::did_you_mean(.suggestion)
+    )]
+    InvalidColorType {
+        name: String,
+        suggestion: Option<String>,
+    },
+    #[error("Unknown color name `{name}`{}", suggest::did_you_mean(.suggestion))]
+    UnknownColorName {
+        name: String,
+        suggestion: Option<String>,
+    },
+    #[error("Invalid .cube LUT: {0}")]
+    InvalidLut(String),
+    #[error("Unknown color space `{0}`")]
+    UnknownColorSpace(String),
+    #[error("`{0}` is not a phonetic colour code")]
+    InvalidPhoneticCode(String),
+    #[error("`{input}` is not a valid {color_type} colour")]
+    InvalidColorAs {
+        input: String,
+        color_type: colors::ColorType,
+    },
 }
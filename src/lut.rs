@@ -0,0 +1,175 @@
+//! 3D colour lookup tables, as used for colour grading and film emulation.
+
+use crate::Error;
+
+/// The largest `LUT_3D_SIZE` the `.cube` specification allows.
+const MAX_SIZE: usize = 256;
+
+/// A 3D lookup table mapping RGB to RGB, sampled on an evenly spaced grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lut3d {
+    size: usize,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    /// Output colours with red changing fastest, then green, then blue
+    table: Vec<[f32; 3]>,
+}
+
+impl Lut3d {
+    /// Parses a 3D LUT in the Adobe/Resolve `.cube` format.
+    pub fn from_cube(source: &str) -> Result<Self, Error> {
+        let invalid = |reason: String| Error::InvalidLut(reason);
+        let triple = |values: &[&str], line: usize| -> Result<[f32; 3], Error> {
+            match values {
+                [r, g, b] => {
+                    let parse = |v: &str| {
+                        v.parse::<f32>()
+                            .ok()
+                            .filter(|v| v.is_finite())
+                            .ok_or_else(|| {
+                                invalid(format!("`{}` isn't a number on line {}", v, line))
+                            })
+                    };
+                    Ok([parse(r)?, parse(g)?, parse(b)?])
+                }
+                _ => Err(invalid(format!("expected three numbers on line {}", line))),
+            }
+        };
+
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+
+        for (i, line) in source.lines().enumerate() {
+            let line_number = i + 1;
+            let words: Vec<&str> = line.split_whitespace().collect();
+
+            match words.as_slice() {
+                [] => {}
+                [first, ..] if first.starts_with('#') => {}
+                ["TITLE", ..] => {}
+                ["LUT_3D_SIZE", n] => {
+                    let n = n
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|n| (2..=MAX_SIZE).contains(n))
+                        .ok_or_else(|| {
+                            invalid(format!(
+                                "invalid LUT_3D_SIZE `{}`, expected 2 to {}",
+                                n, MAX_SIZE
+                            ))
+                        })?;
+                    size = Some(n);
+                }
+                ["LUT_1D_SIZE", ..] => return Err(invalid("1D LUTs aren't supported".to_string())),
+                ["DOMAIN_MIN", values @ ..] => domain_min = triple(values, line_number)?,
+                ["DOMAIN_MAX", values @ ..] => domain_max = triple(values, line_number)?,
+                values => table.push(triple(values, line_number)?),
+            }
+        }
+
+        let size = size.ok_or_else(|| invalid("missing LUT_3D_SIZE".to_string()))?;
+        if table.len() != size.pow(3) {
+            return Err(invalid(format!(
+                "expected {} entries for a size {} LUT, found {}",
+                size.pow(3),
+                size,
+                table.len()
+            )));
+        }
+        if (0..3).any(|c| domain_min[c] >= domain_max[c]) {
+            return Err(invalid("DOMAIN_MIN must be below DOMAIN_MAX".to_string()));
+        }
+
+        Ok(Self {
+            size,
+            domain_min,
+            domain_max,
+            table,
+        })
+    }
+
+    fn entry(&self, red: usize, green: usize, blue: usize) -> [f32; 3] {
+        self.table[red + green * self.size + blue * self.size * self.size]
+    }
+
+    /// Looks up an RGB triple, interpolating trilinearly between grid points.
+    /// Inputs outside the LUT's domain are clamped to its edges.
+    pub fn apply(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let last = (self.size - 1) as f32;
+        let position = [0, 1, 2].map(|c| {
+            let t = (rgb[c] - self.domain_min[c]) / (self.domain_max[c] - self.domain_min[c]);
+            t.clamp(0.0, 1.0) * last
+        });
+        // The lower corner of the grid cell, and how far across it the input is
+        let lower = position.map(|p| (p as usize).min(self.size - 2));
+        let t = [0, 1, 2].map(|c| position[c] - lower[c] as f32);
+
+        let mut output = [0.0; 3];
+        for corner in 0..8 {
+            let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+            let weight: f32 = (0..3)
+                .map(|c| if offset[c] == 1 { t[c] } else { 1.0 - t[c] })
+                .product();
+            let entry = self.entry(
+                lower[0] + offset[0],
+                lower[1] + offset[1],
+                lower[2] + offset[2],
+            );
+            for c in 0..3 {
+                output[c] += weight * entry[c];
+            }
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A size 2 LUT that maps each corner of the cube through `f`.
+    fn cube(f: impl Fn([f32; 3]) -> [f32; 3]) -> String {
+        let mut source = "TITLE \"test\"\n# A comment\nLUT_3D_SIZE 2\n".to_string();
+        for b in 0..2 {
+            for g in 0..2 {
+                for r in 0..2 {
+                    let [r, g, b] = f([r as f32, g as f32, b as f32]);
+                    source.push_str(&format!("{} {} {}\n", r, g, b));
+                }
+            }
+        }
+        source
+    }
+
+    #[test]
+    fn identity_and_inversion() {
+        let identity = Lut3d::from_cube(&cube(|rgb| rgb)).unwrap();
+        assert_eq!(identity.apply([0.25, 0.5, 0.75]), [0.25, 0.5, 0.75]);
+
+        let invert = Lut3d::from_cube(&cube(|rgb| rgb.map(|c| 1.0 - c))).unwrap();
+        assert_eq!(invert.apply([0.25, 0.5, 1.0]), [0.75, 0.5, 0.0]);
+        // Clamped to the domain
+        assert_eq!(invert.apply([2.0, -1.0, 0.0]), [0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn invalid_cubes() {
+        assert!(Lut3d::from_cube("0 0 0\n").is_err());
+        assert!(Lut3d::from_cube("LUT_3D_SIZE 2\n0 0 0\n").is_err());
+        assert!(Lut3d::from_cube("LUT_1D_SIZE 2\n0 0 0\n1 1 1\n").is_err());
+        assert!(Lut3d::from_cube(&cube(|rgb| rgb).replace("0 1 1", "0 x 1")).is_err());
+
+        // Non-finite values
+        assert!(Lut3d::from_cube(&cube(|rgb| rgb).replace("0 1 1", "0 nan 1")).is_err());
+        assert!(Lut3d::from_cube(&cube(|rgb| rgb).replace("0 1 1", "0 1 inf")).is_err());
+        assert!(Lut3d::from_cube(&format!("DOMAIN_MAX inf 1 1\n{}", cube(|rgb| rgb))).is_err());
+
+        // Sizes that would overflow the table length are rejected before it's counted
+        let error = Lut3d::from_cube("LUT_3D_SIZE 4294967296\n").unwrap_err();
+        assert!(error.to_string().contains("expected 2 to 256"), "{}", error);
+        assert!(Lut3d::from_cube("LUT_3D_SIZE 257\n").is_err());
+    }
+}
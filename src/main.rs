@@ -1,29 +1,118 @@
-use color::colors::{Color, ColorType};
+use color::colors::{Color, ColorType, DEFAULT_COLOR_NAME};
+use color::lut::Lut3d;
 use std::error::Error;
 use structopt::StructOpt;
 
 #[derive(StructOpt, Debug)]
 #[structopt(name = "color", about = "A utility for converting and picking colours")]
 struct Opt {
-    color: Color,
+    color: String,
+    /// Only parse the colour as this notation, e.g. `hex` to accept `010`
+    #[structopt(long = "from")]
+    from: Option<ColorType>,
+    /// Accept sloppy input, such as hex without `#` or `rgb 255 0 0`
+    #[structopt(long = "lenient", conflicts_with = "from")]
+    lenient: bool,
+    /// Only accept valid CSS, with values in range
+    #[structopt(long = "strict", conflicts_with_all = &["from", "lenient"])]
+    strict: bool,
+    /// The colour to use in place of `currentcolor`
+    #[structopt(long = "current-color")]
+    current_color: Option<String>,
+    /// Snap to a nearby colour with round HSL values
+    #[structopt(long = "beautify")]
+    beautify: bool,
+    /// Apply a 3D LUT in `.cube` format to the colour
+    #[structopt(long = "lut")]
+    lut: Option<std::path::PathBuf>,
     #[structopt(short = "o", long = "output")]
     output: Option<Vec<ColorType>>,
+    /// Put the nearest coloured square emoji before each output
+    #[structopt(long = "emoji")]
+    emoji: bool,
+    /// The colour name to use for LaTeX and troff output
+    #[structopt(long = "name", default_value = DEFAULT_COLOR_NAME)]
+    name: String,
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let Opt { color, output } = Opt::from_args();
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("Error: {}", err);
+        // Point at where parsing went wrong
+        if let Some(color::Error::InvalidColor { input, column, .. }) = err.downcast_ref() {
+            eprintln!("  {}", input);
+            eprintln!("  {:>width$}", "^", width = column);
+        }
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        color,
+        from,
+        lenient,
+        strict,
+        current_color,
+        beautify,
+        lut,
+        output,
+        emoji,
+        name,
+    } = Opt::from_args();
+
+    let color = match from {
+        Some(color_type) => Color::parse_as(&color, color_type)?,
+        None if strict => Color::from_str_strict(&color)?,
+        None if lenient => Color::from_str_lenient(&color)?,
+        None => color.parse::<Color>()?,
+    };
+    let color = match current_color {
+        Some(current) => color.resolve_current_color(&current.parse()?),
+        None => color,
+    };
+    let color = if beautify { color.beautify() } else { color };
+    let color = match lut {
+        Some(path) => color.apply_lut(&Lut3d::from_cube(&std::fs::read_to_string(path)?)?),
+        None => color,
+    };
+
+    let prefix = if emoji {
+        format!("{} ", color.nearest_emoji())
+    } else {
+        String::new()
+    };
 
     if let Some(v) = output {
         for c in v {
-            let color = match c {
+            let output = match c {
                 ColorType::Hsl => color.hsl_string(),
                 ColorType::Rgb => color.rgb_string(),
                 ColorType::Hex => color.hex_string(),
+                ColorType::Name => color.name_string(),
+                ColorType::Hwb => color.hwb_string(),
+                ColorType::Hsv => color.hsv_string(),
+                ColorType::Lab => color.lab_string(),
+                ColorType::Lch => color.lch_string(),
+                ColorType::Oklab => color.oklab_string(),
+                ColorType::Oklch => color.oklch_string(),
+                ColorType::Cmyk => color.cmyk_string(),
+                ColorType::Ycbcr => color.ycbcr_string(),
+                ColorType::Office => color.office_string(),
+                ColorType::Vba => color.vba_string(),
+                ColorType::Latex => color.latex_string(&name),
+                ColorType::LatexHtml => color.latex_html_string(&name),
+                ColorType::Typst => color.typst_string(),
+                ColorType::Troff => color.troff_string(&name),
+                ColorType::Ansi => color.ansi_string(),
+                // Not a notation `--output` accepts, so leave it to the colour's formatter
+                ColorType::CurrentColor => color.to_string(),
+                ColorType::Color(space) => color.color_string(space),
             };
-            println!("{}", color);
+            println!("{}{}", prefix, output);
         }
     } else {
-        println!("{}", color);
+        println!("{}{}", prefix, color);
     }
 
     Ok(())
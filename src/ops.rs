@@ -0,0 +1,106 @@
+//! Arithmetic on colours, for quick additive-light calculations like `red + green`.
+//!
+//! Operations happen on linear-light RGB, so adding two lights behaves physically
+//! rather than adding their gamma-encoded values. Results are clamped back into the
+//! sRGB gamut, and keep the alpha and notation of the left-hand colour.
+
+use crate::colors::Color;
+use std::ops::{Add, Mul, Sub};
+
+fn to_linear(color: &Color) -> [f32; 3] {
+    let (red, green, blue, _) = color.linear_rgb();
+
+    [red, green, blue]
+}
+
+fn from_linear(like: &Color, [red, green, blue]: [f32; 3]) -> Color {
+    let (_, _, _, alpha) = like.rgb();
+    // Clamp here, as `from_linear_rgb` keeps light outside of sRGB
+    let [red, green, blue] = [red, green, blue].map(|c| c.clamp(0.0, 1.0));
+
+    Color::from_linear_rgb(red, green, blue, alpha).with_type(like.color_type())
+}
+
+impl Add for Color {
+    type Output = Color;
+
+    fn add(self, other: Color) -> Color {
+        let [r1, g1, b1] = to_linear(&self);
+        let [r2, g2, b2] = to_linear(&other);
+
+        from_linear(&self, [r1 + r2, g1 + g2, b1 + b2])
+    }
+}
+
+impl Sub for Color {
+    type Output = Color;
+
+    fn sub(self, other: Color) -> Color {
+        let [r1, g1, b1] = to_linear(&self);
+        let [r2, g2, b2] = to_linear(&other);
+
+        from_linear(&self, [r1 - r2, g1 - g2, b1 - b2])
+    }
+}
+
+impl Mul<f32> for Color {
+    type Output = Color;
+
+    /// Scales the colour's light intensity.
+    fn mul(self, factor: f32) -> Color {
+        let linear = to_linear(&self).map(|c| c * factor);
+
+        from_linear(&self, linear)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn add_colors() {
+        let red = Color::from_rgb(255.0, 0.0, 0.0, 1.0);
+        let green = Color::from_rgb(0.0, 255.0, 0.0, 0.5);
+        assert_eq!((255.0, 255.0, 0.0, 1.0), (red + green).rgb());
+
+        // Equal linear light doesn't double the encoded value
+        let grey = Color::from_rgb(128.0, 128.0, 128.0, 1.0);
+        assert_eq!((176.0, 176.0, 176.0, 1.0), (grey.clone() + grey).rgb());
+    }
+
+    #[test]
+    fn subtract_colors() {
+        let white = Color::from_rgb(255.0, 255.0, 255.0, 1.0);
+        let blue = Color::from_rgb(0.0, 0.0, 255.0, 1.0);
+        assert_eq!((255.0, 255.0, 0.0, 1.0), (white - blue.clone()).rgb());
+
+        let black = Color::from_rgb(0.0, 0.0, 0.0, 1.0);
+        assert_eq!((0.0, 0.0, 0.0, 1.0), (black - blue).rgb());
+    }
+
+    #[test]
+    fn scale_colors() {
+        let white = Color::from_rgb(255.0, 255.0, 255.0, 1.0);
+        assert_eq!((188.0, 188.0, 188.0, 1.0), (white.clone() * 0.5).rgb());
+        assert_eq!((255.0, 255.0, 255.0, 1.0), (white.clone() * 2.0).rgb());
+    }
+
+    #[test]
+    fn results_stay_in_srgb() {
+        let white = Color::from_rgb(255.0, 255.0, 255.0, 1.0);
+        let bright = white.clone() * 2.0;
+        assert!(bright.in_srgb_gamut());
+        assert_eq!(white.oklch(), bright.oklch());
+        assert_eq!(white.lab(), bright.lab());
+        assert_eq!("rgb(255 255 255)", bright.to_string());
+
+        let red = Color::from_rgb(255.0, 0.0, 0.0, 1.0);
+        let doubled = red.clone() + red.clone();
+        assert!(doubled.in_srgb_gamut());
+        assert_eq!(red.oklch(), doubled.oklch());
+
+        let blue = Color::from_rgb(0.0, 0.0, 255.0, 1.0);
+        assert!((blue - red).in_srgb_gamut());
+    }
+}
@@ -1,13 +1,142 @@
-use crate::colors::Color;
+use crate::colors::{Color, ColorSpace, ColorType};
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::{space0, space1},
-    combinator::{eof, map, opt},
+    bytes::complete::{take_while1, take_while_m_n},
+    character::complete::{alpha1, digit1, multispace0, multispace1},
+    combinator::{eof, fail, map, map_opt, map_res, opt, peek, verify},
+    error::{context, ContextError, ErrorKind, FromExternalError},
+    multi::{fold_many0, many1, separated_list1},
     number::complete::float,
     sequence::{delimited, preceded, terminated, tuple},
-    IResult,
 };
+use std::{cmp::Ordering, ops::Range};
+
+/// The furthest point the parser got to before failing, and everything it would
+/// have accepted there.
+#[derive(Debug, PartialEq)]
+pub struct ParseError<I> {
+    input: I,
+    expected: Vec<String>,
+}
+
+type IResult<I, O> = nom::IResult<I, O, ParseError<I>>;
+
+impl<'a> nom::error::ParseError<&'a str> for ParseError<&'a str> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        let expected = match kind {
+            ErrorKind::Float => Some("a number"),
+            ErrorKind::MultiSpace => Some("a space"),
+            ErrorKind::Eof => Some("the end of the colour"),
+            _ => None,
+        };
+
+        Self {
+            input,
+            expected: expected.into_iter().map(String::from).collect(),
+        }
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn or(mut self, other: Self) -> Self {
+        // Less input left means that alternative got further
+        match self.input.len().cmp(&other.input.len()) {
+            Ordering::Less => self,
+            Ordering::Greater => other,
+            Ordering::Equal => {
+                for expected in other.expected {
+                    if !self.expected.contains(&expected) {
+                        self.expected.push(expected);
+                    }
+                }
+                self
+            }
+        }
+    }
+}
+
+impl<'a> ContextError<&'a str> for ParseError<&'a str> {
+    fn add_context(input: &'a str, context: &'static str, other: Self) -> Self {
+        // Only describe failures that happened right where the context starts
+        if other.input.len() == input.len() {
+            Self {
+                input,
+                expected: vec![context.to_string()],
+            }
+        } else {
+            other
+        }
+    }
+}
+
+impl<'a, E> FromExternalError<&'a str, E> for ParseError<&'a str> {
+    fn from_external_error(input: &'a str, kind: ErrorKind, _: E) -> Self {
+        nom::error::ParseError::from_error_kind(input, kind)
+    }
+}
+
+/// Like nom's `tag`, but ignores ASCII case and records the token that was expected.
+fn tag<'a>(token: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    move |input: &'a str| match input.get(..token.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(token) => Ok((&input[token.len()..], prefix)),
+        _ => Err(nom::Err::Error(ParseError {
+            input,
+            expected: vec![format!("'{}'", token)],
+        })),
+    }
+}
+
+/// A finite number. nom's `float` also accepts `nan` and `inf`, which CSS doesn't, and
+/// NaN is how `none` is parsed.
+fn number(input: &str) -> IResult<&str, f32> {
+    context("a number", verify(float, |n: &f32| n.is_finite()))(input)
+}
+
+/// Turns a parse failure into an `Error::InvalidColor` describing where in `input`
+/// it went wrong.
+pub(crate) fn invalid_color(input: &str, err: nom::Err<ParseError<&str>>) -> crate::Error {
+    let (rest, expected, failure) = match err {
+        nom::Err::Error(e) => (e.input, e.expected, false),
+        nom::Err::Failure(e) => (e.input, e.expected, true),
+        nom::Err::Incomplete(_) => ("", vec![], false),
+    };
+    let offset = input.len() - rest.len();
+    let expected = if (offset == 0 && !failure) || expected.is_empty() {
+        // Every notation fails at the start, so listing them all isn't helpful. A
+        // failure comes from the one notation that matched, though.
+        vec!["a colour".to_string()]
+    } else {
+        expected
+    };
+
+    let token_len = rest
+        .find(|c: char| c.is_whitespace() || ",/()".contains(c))
+        .unwrap_or(rest.len());
+    let found = match rest.chars().next() {
+        None => "the end of the input".to_string(),
+        Some(c) if token_len == 0 => format!("`{}`", c),
+        Some(_) => format!("`{}`", &rest[..token_len]),
+    };
+
+    crate::Error::InvalidColor {
+        input: input.to_string(),
+        offset,
+        column: input[..offset].chars().count() + 1,
+        found,
+        expected,
+    }
+}
+
+/// Lists alternatives as `a, b or c`.
+pub(crate) fn one_of(expected: &[String]) -> String {
+    match expected {
+        [] => String::new(),
+        [only] => only.clone(),
+        [init @ .., last] => format!("{} or {}", init.join(", "), last),
+    }
+}
 
 #[derive(Debug, PartialEq)]
 enum Angle {
@@ -22,7 +151,7 @@ impl Angle {
         match self {
             Self::Degrees(deg) => *deg,
             Self::Radians(rad) => rad.to_degrees(),
-            Self::Gradians(grad) => unimplemented!(),
+            Self::Gradians(grad) => grad * 0.9,
             Self::Turns(turns) => turns * 360.0,
         }
     }
@@ -30,54 +159,72 @@ impl Angle {
 
 fn angle(input: &str) -> IResult<&str, Angle> {
     let parser = tuple((
-        float,
+        number,
         opt(alt((tag("deg"), tag("rad"), tag("grad"), tag("turn")))),
     ));
 
-    map(parser, |(val, unit)| match unit {
-        None | Some("deg") => Angle::Degrees(val),
-        Some("rad") => Angle::Radians(val),
-        Some("grad") => Angle::Gradians(val),
-        Some("turn") => Angle::Turns(val),
-        _ => unreachable!(),
+    map(parser, |(val, unit)| {
+        match unit.map(str::to_ascii_lowercase).as_deref() {
+            None | Some("deg") => Angle::Degrees(val),
+            Some("rad") => Angle::Radians(val),
+            Some("grad") => Angle::Gradians(val),
+            Some("turn") => Angle::Turns(val),
+            _ => unreachable!(),
+        }
     })(input)
 }
 
+/// The `none` keyword for a missing component. It's parsed as NaN, which `number`
+/// never returns, and turned into a mask by `with_none` when the colour is built.
+fn none(input: &str) -> IResult<&str, f32> {
+    map(tag("none"), |_| f32::NAN)(input)
+}
+
+/// Builds a colour from parsed components, marking any that were `none` as missing.
+/// Missing components convert as zero, as in CSS.
+fn with_none<const N: usize>(components: [f32; N], build: impl FnOnce([f32; N]) -> Color) -> Color {
+    let missing = components.map(f32::is_nan);
+    let components = components.map(|c| if c.is_nan() { 0.0 } else { c });
+
+    build(components).with_missing(&missing)
+}
+
+fn angle_or_none(input: &str) -> IResult<&str, Angle> {
+    alt((map(none, Angle::Degrees), angle))(input)
+}
+
 fn percentage(input: &str) -> IResult<&str, f32> {
-    terminated(float, tag("%"))(input)
+    terminated(number, tag("%"))(input)
 }
 
 fn hsl_values(input: &str) -> IResult<&str, (Angle, f32, f32, Option<f32>)> {
     let parser_commas = delimited(
-        space0,
+        multispace0,
         tuple((
             angle,
-            delimited(space0, tag(","), space0),
-            alt((percentage, float)),
-            delimited(space0, tag(","), space0),
-            alt((percentage, float)),
+            delimited(multispace0, tag(","), multispace0),
+            alt((percentage, number)),
+            delimited(multispace0, tag(","), multispace0),
+            alt((percentage, number)),
             opt(preceded(
-                delimited(space0, tag(","), space0),
-                alt((map(percentage, |p| p / 100.0), float)),
+                delimited(multispace0, tag(","), multispace0),
+                alt((map(percentage, |p| p / 100.0), number)),
             )),
         )),
-        space0,
+        multispace0,
     );
 
     let parser_spaces = delimited(
-        space0,
+        multispace0,
         tuple((
-            angle,
-            space1,
-            alt((percentage, float)),
-            space1,
-            alt((percentage, float)),
-            opt(preceded(
-                delimited(space0, tag("/"), space0),
-                alt((map(percentage, |p| p / 100.0), float)),
-            )),
+            angle_or_none,
+            multispace1,
+            alt((percentage, number, none)),
+            multispace1,
+            alt((percentage, number, none)),
+            slash_alpha,
         )),
-        space0,
+        multispace0,
     );
     let parser = alt((parser_commas, parser_spaces));
     map(parser, |(angle, _, sat, _, lum, alpha)| {
@@ -102,117 +249,1348 @@ fn hsl_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
     ))
 }
 
-fn comma_separated_percentages(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
-    map(
-        tuple((
-            percentage,
-            delimited(space0, tag(","), space0),
-            percentage,
-            delimited(space0, tag(","), space0),
-            percentage,
-            opt(preceded(delimited(space0, tag(","), space0), percentage)),
-        )),
-        |(p1, _, p2, _, p3, p4)| {
-            (
-                percentage_to_color_255(p1),
-                percentage_to_color_255(p2),
-                percentage_to_color_255(p3),
-                (p4.map(|x| x / 100.0)),
-            )
-        }, // TODO: Convert these percentages to 0-255 floats
-    )(input)
+fn hsv_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+    // HSV shares HSL's syntax, with value in place of luminosity
+    let (input, (hue, saturation, value, alpha)) = preceded(
+        alt((tag("hsva"), tag("hsv"), tag("hsba"), tag("hsb"))),
+        delimited(tag("("), hsl_values, tag(")")),
+    )(input)?;
+
+    Ok((
+        input,
+        (hue.to_degrees(), saturation, value, alpha.unwrap_or(1.0)),
+    ))
+}
+
+fn slash_alpha(input: &str) -> IResult<&str, Option<f32>> {
+    opt(preceded(
+        delimited(multispace0, tag("/"), multispace0),
+        alt((map(percentage, |p| p / 100.0), number, none)),
+    ))(input)
+}
+
+fn hwb_values(input: &str) -> IResult<&str, (Angle, f32, f32, Option<f32>)> {
+    let parser = delimited(
+        multispace0,
+        tuple((
+            angle_or_none,
+            multispace1,
+            alt((percentage, number, none)),
+            multispace1,
+            alt((percentage, number, none)),
+            slash_alpha,
+        )),
+        multispace0,
+    );
+
+    map(parser, |(angle, _, white, _, black, alpha)| {
+        (angle, white, black, alpha)
+    })(input)
+}
+
+fn hwb_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+    let (input, (hue, whiteness, blackness, alpha)) =
+        preceded(tag("hwb"), delimited(tag("("), hwb_values, tag(")")))(input)?;
+
+    Ok((
+        input,
+        (hue.to_degrees(), whiteness, blackness, alpha.unwrap_or(1.0)),
+    ))
+}
+
+fn lab_values(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
+    // For a and b, 100% is 125
+    let parser = delimited(
+        multispace0,
+        tuple((
+            alt((percentage, number, none)),
+            multispace1,
+            alt((map(percentage, |p| p * 1.25), number, none)),
+            multispace1,
+            alt((map(percentage, |p| p * 1.25), number, none)),
+            slash_alpha,
+        )),
+        multispace0,
+    );
+
+    map(parser, |(lightness, _, a, _, b, alpha)| {
+        (lightness, a, b, alpha)
+    })(input)
+}
+
+fn lab_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+    let (input, (lightness, a, b, alpha)) =
+        preceded(tag("lab"), delimited(tag("("), lab_values, tag(")")))(input)?;
+
+    Ok((input, (lightness, a, b, alpha.unwrap_or(1.0))))
+}
+
+fn lch_values(input: &str) -> IResult<&str, (f32, f32, Angle, Option<f32>)> {
+    // For chroma, 100% is 150
+    let parser = delimited(
+        multispace0,
+        tuple((
+            alt((percentage, number, none)),
+            multispace1,
+            alt((map(percentage, |p| p * 1.5), number, none)),
+            multispace1,
+            angle_or_none,
+            slash_alpha,
+        )),
+        multispace0,
+    );
+
+    map(parser, |(lightness, _, chroma, _, hue, alpha)| {
+        (lightness, chroma, hue, alpha)
+    })(input)
+}
+
+fn lch_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+    let (input, (lightness, chroma, hue, alpha)) =
+        preceded(tag("lch"), delimited(tag("("), lch_values, tag(")")))(input)?;
+
+    Ok((
+        input,
+        (lightness, chroma, hue.to_degrees(), alpha.unwrap_or(1.0)),
+    ))
+}
+
+fn oklab_values(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
+    // Lightness is between 0 and 1, and for a and b 100% is 0.4
+    let parser = delimited(
+        multispace0,
+        tuple((
+            alt((map(percentage, |p| p / 100.0), number, none)),
+            multispace1,
+            alt((map(percentage, |p| p * 0.004), number, none)),
+            multispace1,
+            alt((map(percentage, |p| p * 0.004), number, none)),
+            slash_alpha,
+        )),
+        multispace0,
+    );
+
+    map(parser, |(lightness, _, a, _, b, alpha)| {
+        (lightness, a, b, alpha)
+    })(input)
+}
+
+fn oklab_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+    let (input, (lightness, a, b, alpha)) =
+        preceded(tag("oklab"), delimited(tag("("), oklab_values, tag(")")))(input)?;
+
+    Ok((input, (lightness, a, b, alpha.unwrap_or(1.0))))
+}
+
+fn oklch_values(input: &str) -> IResult<&str, (f32, f32, Angle, Option<f32>)> {
+    // Lightness is between 0 and 1, and for chroma 100% is 0.4
+    let parser = delimited(
+        multispace0,
+        tuple((
+            alt((map(percentage, |p| p / 100.0), number, none)),
+            multispace1,
+            alt((map(percentage, |p| p * 0.004), number, none)),
+            multispace1,
+            angle_or_none,
+            slash_alpha,
+        )),
+        multispace0,
+    );
+
+    map(parser, |(lightness, _, chroma, _, hue, alpha)| {
+        (lightness, chroma, hue, alpha)
+    })(input)
+}
+
+fn oklch_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+    let (input, (lightness, chroma, hue, alpha)) =
+        preceded(tag("oklch"), delimited(tag("("), oklch_values, tag(")")))(input)?;
+
+    Ok((
+        input,
+        (lightness, chroma, hue.to_degrees(), alpha.unwrap_or(1.0)),
+    ))
+}
+
+fn cmyk_values(input: &str) -> IResult<&str, (f32, f32, f32, f32, Option<f32>)> {
+    // Plain numbers are fractions of full ink coverage
+    let ink = || alt((percentage, map(number, |n| n * 100.0)));
+    let comma = || delimited(multispace0, tag(","), multispace0);
+
+    let parser_commas = tuple((
+        ink(),
+        preceded(comma(), ink()),
+        preceded(comma(), ink()),
+        preceded(comma(), ink()),
+        opt(preceded(
+            comma(),
+            alt((map(percentage, |p| p / 100.0), number)),
+        )),
+    ));
+
+    let ink_or_none = || alt((ink(), none));
+    let parser_spaces = tuple((
+        ink_or_none(),
+        preceded(multispace1, ink_or_none()),
+        preceded(multispace1, ink_or_none()),
+        preceded(multispace1, ink_or_none()),
+        slash_alpha,
+    ));
+
+    delimited(
+        multispace0,
+        alt((parser_commas, parser_spaces)),
+        multispace0,
+    )(input)
+}
+
+fn cmyk_color(input: &str) -> IResult<&str, (f32, f32, f32, f32, f32)> {
+    let (input, (cyan, magenta, yellow, black, alpha)) = preceded(
+        alt((tag("device-cmyk"), tag("cmyk"))),
+        delimited(tag("("), cmyk_values, tag(")")),
+    )(input)?;
+
+    Ok((input, (cyan, magenta, yellow, black, alpha.unwrap_or(1.0))))
+}
+
+fn color_space(input: &str) -> IResult<&str, ColorSpace> {
+    context(
+        "a colour space",
+        map_res(
+            take_while1(|c: char| c.is_ascii_alphanumeric() || c == '-'),
+            str::parse,
+        ),
+    )(input)
+}
+
+fn color_function_values(input: &str) -> IResult<&str, (ColorSpace, f32, f32, f32, Option<f32>)> {
+    let component = || alt((map(percentage, |p| p / 100.0), number, none));
+    let parser = delimited(
+        multispace0,
+        tuple((
+            color_space,
+            multispace1,
+            component(),
+            multispace1,
+            component(),
+            multispace1,
+            component(),
+            slash_alpha,
+        )),
+        multispace0,
+    );
+
+    map(parser, |(space, _, c1, _, c2, _, c3, alpha)| {
+        (space, c1, c2, c3, alpha)
+    })(input)
+}
+
+fn color_function(input: &str) -> IResult<&str, (ColorSpace, f32, f32, f32, f32)> {
+    let (input, (space, c1, c2, c3, alpha)) = preceded(
+        tag("color"),
+        delimited(tag("("), color_function_values, tag(")")),
+    )(input)?;
+
+    Ok((input, (space, c1, c2, c3, alpha.unwrap_or(1.0))))
+}
+
+/// The `xyz()`, `xyz-d50()` and `xyz-d65()` shorthands for `color(xyz ...)`.
+fn xyz_color(input: &str) -> IResult<&str, (ColorSpace, f32, f32, f32, f32)> {
+    let space = alt((
+        map(tag("xyz-d50"), |_| ColorSpace::XyzD50),
+        map(tag("xyz-d65"), |_| ColorSpace::XyzD65),
+        map(tag("xyz"), |_| ColorSpace::XyzD65),
+    ));
+    let component = || alt((map(percentage, |p| p / 100.0), number, none));
+    let values = delimited(
+        multispace0,
+        tuple((
+            component(),
+            preceded(multispace1, component()),
+            preceded(multispace1, component()),
+            slash_alpha,
+        )),
+        multispace0,
+    );
+
+    let (input, (space, (x, y, z, alpha))) =
+        tuple((space, delimited(tag("("), values, tag(")"))))(input)?;
+
+    Ok((input, (space, x, y, z, alpha.unwrap_or(1.0))))
+}
+
+fn comma_separated_percentages(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
+    map(
+        tuple((
+            percentage,
+            delimited(multispace0, tag(","), multispace0),
+            percentage,
+            delimited(multispace0, tag(","), multispace0),
+            percentage,
+            opt(preceded(
+                delimited(multispace0, tag(","), multispace0),
+                alt((map(percentage, |p| p / 100.0), number)),
+            )),
+        )),
+        |(p1, _, p2, _, p3, p4)| {
+            (
+                percentage_to_color_255(p1),
+                percentage_to_color_255(p2),
+                percentage_to_color_255(p3),
+                p4,
+            )
+        }, // TODO: Convert these percentages to 0-255 floats
+    )(input)
+}
+
+fn percentage_to_color_255(input: f32) -> f32 {
+    (input / 100.0) * 255.0
+}
+
+fn space_separated_percentages(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
+    map(
+        tuple((
+            alt((percentage, none)),
+            multispace1,
+            alt((percentage, none)),
+            multispace1,
+            alt((percentage, none)),
+            slash_alpha,
+        )),
+        |(p1, _, p2, _, p3, p4)| {
+            (
+                percentage_to_color_255(p1),
+                percentage_to_color_255(p2),
+                percentage_to_color_255(p3),
+                p4,
+            )
+        },
+    )(input)
+}
+
+fn comma_separated_floats(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
+    map(
+        tuple((
+            number,
+            delimited(multispace0, tag(","), multispace0),
+            number,
+            delimited(multispace0, tag(","), multispace0),
+            number,
+            opt(preceded(
+                delimited(multispace0, tag(","), multispace0),
+                alt((map(percentage, |p| p / 100.0), number)),
+            )),
+        )),
+        |(p1, _, p2, _, p3, p4)| (p1, p2, p3, p4),
+    )(input)
+}
+
+fn space_separated_floats(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
+    map(
+        tuple((
+            alt((number, none)),
+            multispace1,
+            alt((number, none)),
+            multispace1,
+            alt((number, none)),
+            slash_alpha,
+        )),
+        |(p1, _, p2, _, p3, p4)| (p1, p2, p3, p4),
+    )(input)
+}
+
+fn ycbcr_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+    let (input, (luma, cb, cr, alpha)) = preceded(
+        alt((tag("ycbcr"), tag("yuv"))),
+        delimited(
+            tag("("),
+            delimited(
+                multispace0,
+                alt((comma_separated_floats, space_separated_floats)),
+                multispace0,
+            ),
+            tag(")"),
+        ),
+    )(input)?;
+
+    Ok((input, (luma, cb, cr, alpha.unwrap_or(1.0))))
+}
+
+fn rgb_values(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
+    // Combine the above with alpha values
+    delimited(
+        multispace0,
+        alt((
+            comma_separated_percentages,
+            comma_separated_floats,
+            space_separated_percentages,
+            space_separated_floats,
+        )),
+        multispace0,
+    )(input)
+}
+
+fn rgb_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+    let (input, (red, green, blue, alpha)) = preceded(
+        alt((tag("rgba"), tag("rgb"))),
+        delimited(tag("("), rgb_values, tag(")")),
+    )(input)?;
+
+    Ok((input, (red, green, blue, alpha.unwrap_or(1.0))))
+}
+
+fn hex_channel(digits: &str) -> f32 {
+    let value = u8::from_str_radix(digits, 16).expect("hex digits are validated by the parser");
+
+    if digits.len() == 1 {
+        // Short forms repeat each digit, so `f` means `ff`.
+        (value * 17) as f32
+    } else {
+        value as f32
+    }
+}
+
+pub(crate) fn hex_digits(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+    context(
+        "3, 4, 6 or 8 hex digits",
+        map_opt(
+            take_while_m_n(3, 8, |c: char| c.is_ascii_hexdigit()),
+            |digits: &str| {
+                let width = match digits.len() {
+                    3 | 4 => 1,
+                    6 | 8 => 2,
+                    _ => return None,
+                };
+                let channel = |i: usize| hex_channel(&digits[i * width..(i + 1) * width]);
+                let alpha = match digits.len() {
+                    3 | 6 => 1.0,
+                    _ => channel(3) / 255.0,
+                };
+
+                Some((channel(0), channel(1), channel(2), alpha))
+            },
+        ),
+    )(input)
+}
+
+fn hex_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+    preceded(tag("#"), hex_digits)(input)
+}
+
+fn named_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+    context("a colour name", map_opt(alpha1, crate::names::lookup))(input)
+}
+
+/// An xterm palette index such as `ansi:208`, or one of the 16 standard colours by
+/// name. Without the `ansi:` prefix only the `bright` names are accepted, since the
+/// others are also CSS names.
+fn ansi_color(input: &str) -> IResult<&str, u8> {
+    alt((
+        preceded(
+            tag("ansi:"),
+            alt((
+                map_res(digit1, str::parse),
+                map_opt(alpha1, crate::ansi::index_of),
+            )),
+        ),
+        map_opt(
+            verify(alpha1, |name: &str| {
+                name.to_ascii_lowercase().starts_with("bright")
+            }),
+            crate::ansi::index_of,
+        ),
+    ))(input)
+}
+
+/// The `currentcolor` keyword, which like all CSS keywords ignores case.
+fn current_color(input: &str) -> IResult<&str, Color> {
+    map(
+        verify(alpha1, |word: &str| {
+            word.eq_ignore_ascii_case("currentcolor")
+        }),
+        |_| Color::current_color(),
+    )(input)
+}
+
+fn space_color((space, c1, c2, c3, alpha): (ColorSpace, f32, f32, f32, f32)) -> Color {
+    with_none([c1, c2, c3, alpha], |[c1, c2, c3, alpha]| {
+        Color::from_space(space, c1, c2, c3, alpha)
+    })
+}
+
+fn hex_to_color((red, green, blue, alpha): (f32, f32, f32, f32)) -> Color {
+    Color::from_rgb(red, green, blue, alpha).with_type(ColorType::Hex)
+}
+
+fn name_to_color((red, green, blue, alpha): (f32, f32, f32, f32)) -> Color {
+    Color::from_rgb(red, green, blue, alpha).with_type(ColorType::Name)
+}
+
+/// The channel keywords of one colour function's relative syntax, e.g. `r g b` for `rgb()`.
+struct RelativeSyntax {
+    channels: [&'static str; 3],
+    /// What 100% resolves to for each component, if percentages are allowed there
+    percentages: [Option<f32>; 3],
+    /// Breaks the origin colour down into the function's components and alpha
+    origin: fn(&Color) -> (f32, f32, f32, f32),
+}
+
+const RELATIVE_RGB: RelativeSyntax = RelativeSyntax {
+    channels: ["r", "g", "b"],
+    percentages: [Some(255.0), Some(255.0), Some(255.0)],
+    origin: Color::rgb,
+};
+
+const RELATIVE_HSL: RelativeSyntax = RelativeSyntax {
+    channels: ["h", "s", "l"],
+    percentages: [None, Some(100.0), Some(100.0)],
+    origin: Color::hsl,
+};
+
+const RELATIVE_HWB: RelativeSyntax = RelativeSyntax {
+    channels: ["h", "w", "b"],
+    percentages: [None, Some(100.0), Some(100.0)],
+    origin: Color::hwb,
+};
+
+const RELATIVE_LAB: RelativeSyntax = RelativeSyntax {
+    channels: ["l", "a", "b"],
+    percentages: [Some(100.0), Some(125.0), Some(125.0)],
+    origin: Color::lab,
+};
+
+const RELATIVE_LCH: RelativeSyntax = RelativeSyntax {
+    channels: ["l", "c", "h"],
+    percentages: [Some(100.0), Some(150.0), None],
+    origin: Color::lch,
+};
+
+const RELATIVE_OKLAB: RelativeSyntax = RelativeSyntax {
+    channels: ["l", "a", "b"],
+    percentages: [Some(1.0), Some(0.4), Some(0.4)],
+    origin: Color::oklab,
+};
+
+const RELATIVE_OKLCH: RelativeSyntax = RelativeSyntax {
+    channels: ["l", "c", "h"],
+    percentages: [Some(1.0), Some(0.4), None],
+    origin: Color::oklch,
+};
+
+/// The origin colour's channel values, as named by a relative colour's keywords.
+struct Channels {
+    names: [&'static str; 3],
+    values: [f32; 4],
+}
+
+impl Channels {
+    fn get(&self, name: &str) -> Option<f32> {
+        if name.eq_ignore_ascii_case("alpha") {
+            return Some(self.values[3]);
+        }
+        self.names
+            .iter()
+            .position(|n| n.eq_ignore_ascii_case(name))
+            .map(|i| self.values[i])
+    }
+}
+
+/// A number, percentage, angle, channel keyword or bracketed expression.
+fn calc_value<'a>(
+    input: &'a str,
+    channels: &Channels,
+    percentage_of: Option<f32>,
+) -> IResult<&'a str, f32> {
+    alt((
+        map_opt(percentage, |p| percentage_of.map(|of| p / 100.0 * of)),
+        map(angle, |angle| angle.to_degrees()),
+        context(
+            "a channel keyword",
+            map_opt(alpha1, |name| channels.get(name)),
+        ),
+        delimited(
+            terminated(alt((tag("calc("), tag("("))), multispace0),
+            |input| calc_sum(input, channels, percentage_of),
+            preceded(multispace0, tag(")")),
+        ),
+    ))(input)
+}
+
+fn calc_product<'a>(
+    input: &'a str,
+    channels: &Channels,
+    percentage_of: Option<f32>,
+) -> IResult<&'a str, f32> {
+    let (input, first) = calc_value(input, channels, percentage_of)?;
+    fold_many0(
+        tuple((
+            delimited(multispace0, alt((tag("*"), tag("/"))), multispace0),
+            |input| calc_value(input, channels, percentage_of),
+        )),
+        move || first,
+        |acc, (op, value)| if op == "*" { acc * value } else { acc / value },
+    )(input)
+}
+
+fn calc_sum<'a>(
+    input: &'a str,
+    channels: &Channels,
+    percentage_of: Option<f32>,
+) -> IResult<&'a str, f32> {
+    let (input, first) = calc_product(input, channels, percentage_of)?;
+    fold_many0(
+        tuple((
+            delimited(multispace0, alt((tag("+"), tag("-"))), multispace0),
+            |input| calc_product(input, channels, percentage_of),
+        )),
+        move || first,
+        |acc, (op, value)| if op == "+" { acc + value } else { acc - value },
+    )(input)
+}
+
+fn relative_component<'a>(
+    input: &'a str,
+    channels: &Channels,
+    percentage_of: Option<f32>,
+) -> IResult<&'a str, f32> {
+    let finite_value = |input| {
+        let (rest, value) = calc_value(input, channels, percentage_of)?;
+        // Dividing by zero would otherwise be mistaken for `none`
+        if value.is_finite() {
+            Ok((rest, value))
+        } else {
+            let expected = "a calculation with a finite result".to_string();
+            Err(css_failure(input, expected))
+        }
+    };
+
+    alt((none, finite_value))(input)
+}
+
+/// `from <color>`, the origin colour of relative colour syntax.
+fn origin_color(input: &str) -> IResult<&str, Color> {
+    delimited(
+        multispace0,
+        preceded(terminated(tag("from"), multispace1), any_color),
+        multispace1,
+    )(input)
+}
+
+/// The three components and optional alpha after the origin colour. Alpha defaults
+/// to the origin's.
+fn relative_components<'a>(
+    input: &'a str,
+    channels: &Channels,
+    percentages: [Option<f32>; 3],
+) -> IResult<&'a str, (f32, f32, f32, f32)> {
+    let (input, (c1, _, c2, _, c3, alpha, _)) = tuple((
+        |input| relative_component(input, channels, percentages[0]),
+        multispace1,
+        |input| relative_component(input, channels, percentages[1]),
+        multispace1,
+        |input| relative_component(input, channels, percentages[2]),
+        opt(preceded(
+            delimited(multispace0, tag("/"), multispace0),
+            |input| relative_component(input, channels, Some(1.0)),
+        )),
+        multispace0,
+    ))(input)?;
+
+    Ok((input, (c1, c2, c3, alpha.unwrap_or(channels.values[3]))))
+}
+
+/// CSS relative colour syntax, e.g. `rgb(from #ff0000 r g b / 0.5)`.
+fn relative_color<'a, F>(
+    function: F,
+    syntax: &'static RelativeSyntax,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (f32, f32, f32, f32)>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, &'a str>,
+{
+    preceded(
+        function,
+        delimited(
+            tag("("),
+            move |input| {
+                let (input, origin) = origin_color(input)?;
+                let (c1, c2, c3, alpha) = (syntax.origin)(&origin);
+                let channels = Channels {
+                    names: syntax.channels,
+                    values: [c1, c2, c3, alpha],
+                };
+                relative_components(input, &channels, syntax.percentages)
+            },
+            tag(")"),
+        ),
+    )
+}
+
+/// `color(from <color> <space> ...)`, whose channel keywords depend on the space.
+fn relative_color_function(input: &str) -> IResult<&str, (ColorSpace, f32, f32, f32, f32)> {
+    let (input, (_, origin)) = tuple((tag("color("), origin_color))(input)?;
+    let (input, (space, _)) = tuple((color_space, multispace1))(input)?;
+
+    let (c1, c2, c3, alpha) = origin.in_space(space);
+    let names = match space {
+        ColorSpace::XyzD50 | ColorSpace::XyzD65 => ["x", "y", "z"],
+        _ => ["r", "g", "b"],
+    };
+    let channels = Channels {
+        names,
+        values: [c1, c2, c3, alpha],
+    };
+
+    let (input, (c1, c2, c3, alpha)) = terminated(
+        |input| relative_components(input, &channels, [Some(1.0); 3]),
+        tag(")"),
+    )(input)?;
+
+    Ok((input, (space, c1, c2, c3, alpha)))
+}
+
+fn typed_color(color_type: ColorType) -> impl FnMut(&str) -> IResult<&str, Color> {
+    move |input| match color_type {
+        ColorType::Hsl => map(
+            alt((
+                hsl_color,
+                relative_color(alt((tag("hsla"), tag("hsl"))), &RELATIVE_HSL),
+            )),
+            |(hue, sat, lum, alpha)| {
+                with_none([hue, sat, lum, alpha], |[h, s, l, a]| {
+                    Color::from_hsl(h, s, l, a)
+                })
+            },
+        )(input),
+        ColorType::Rgb => map(
+            alt((
+                rgb_color,
+                relative_color(alt((tag("rgba"), tag("rgb"))), &RELATIVE_RGB),
+            )),
+            |(red, green, blue, alpha)| {
+                with_none([red, green, blue, alpha], |[r, g, b, a]| {
+                    Color::from_rgb(r, g, b, a)
+                })
+            },
+        )(input),
+        ColorType::Hwb => map(
+            alt((hwb_color, relative_color(tag("hwb"), &RELATIVE_HWB))),
+            |(hue, white, black, alpha)| {
+                with_none([hue, white, black, alpha], |[h, w, b, a]| {
+                    Color::from_hwb(h, w, b, a)
+                })
+            },
+        )(input),
+        ColorType::Hsv => map(hsv_color, |(hue, sat, value, alpha)| {
+            with_none([hue, sat, value, alpha], |[h, s, v, a]| {
+                Color::from_hsv(h, s, v, a)
+            })
+        })(input),
+        ColorType::Lab => map(
+            alt((lab_color, relative_color(tag("lab"), &RELATIVE_LAB))),
+            |(lightness, a, b, alpha)| {
+                with_none([lightness, a, b, alpha], |[l, a, b, alpha]| {
+                    Color::from_lab(l, a, b, alpha)
+                })
+            },
+        )(input),
+        ColorType::Lch => map(
+            alt((lch_color, relative_color(tag("lch"), &RELATIVE_LCH))),
+            |(lightness, chroma, hue, alpha)| {
+                with_none([lightness, chroma, hue, alpha], |[l, c, h, a]| {
+                    Color::from_lch(l, c, h, a)
+                })
+            },
+        )(input),
+        ColorType::Oklab => map(
+            alt((oklab_color, relative_color(tag("oklab"), &RELATIVE_OKLAB))),
+            |(lightness, a, b, alpha)| {
+                with_none([lightness, a, b, alpha], |[l, a, b, alpha]| {
+                    Color::from_oklab(l, a, b, alpha)
+                })
+            },
+        )(input),
+        ColorType::Oklch => map(
+            alt((oklch_color, relative_color(tag("oklch"), &RELATIVE_OKLCH))),
+            |(lightness, chroma, hue, alpha)| {
+                with_none([lightness, chroma, hue, alpha], |[l, c, h, a]| {
+                    Color::from_oklch(l, c, h, a)
+                })
+            },
+        )(input),
+        ColorType::Ycbcr => map(ycbcr_color, |(luma, cb, cr, alpha)| {
+            with_none([luma, cb, cr, alpha], |[y, cb, cr, a]| {
+                Color::from_ycbcr(y, cb, cr, a)
+            })
+        })(input),
+        ColorType::Cmyk => map(cmyk_color, |(cyan, magenta, yellow, black, alpha)| {
+            with_none([cyan, magenta, yellow, black, alpha], |[c, m, y, k, a]| {
+                Color::from_cmyk(c, m, y, k, a)
+            })
+        })(input),
+        ColorType::Color(space) => map(
+            verify(
+                alt((color_function, relative_color_function, xyz_color)),
+                |(parsed, ..)| *parsed == space,
+            ),
+            space_color,
+        )(input),
+        ColorType::Hex => map(preceded(opt(tag("#")), hex_digits), hex_to_color)(input),
+        ColorType::Name => map(named_color, name_to_color)(input),
+        ColorType::CurrentColor => current_color(input),
+        // Bare indexes and basic names are palette entries when asked for explicitly
+        ColorType::Ansi => map(
+            alt((
+                ansi_color,
+                map_res(digit1, str::parse),
+                map_opt(alpha1, crate::ansi::index_of),
+            )),
+            Color::from_ansi,
+        )(input),
+        ColorType::Office
+        | ColorType::Vba
+        | ColorType::Latex
+        | ColorType::LatexHtml
+        | ColorType::Typst
+        | ColorType::Troff => fail(input),
+    }
+}
+
+/// Parses a colour using only the grammar for a single notation. Hex colours may
+/// omit the `#`.
+pub fn parse_color_as(input: &str, color_type: ColorType) -> IResult<&str, Color> {
+    terminated(typed_color(color_type), eof)(input)
+}
+
+/// Whether the input is a hex code missing its `#`, such as `336699`.
+pub(crate) fn is_bare_hex(input: &str) -> bool {
+    matches!(input.len(), 3 | 4 | 6 | 8) && input.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Rewrites sloppy input into something the strict grammar accepts, for lenient
+/// parsing. Bare hex digits get a `#`, and function arguments are given brackets
+/// and separated by spaces, with any extra final argument moved after a `/`.
+pub(crate) fn tidy(input: &str) -> String {
+    if is_bare_hex(input) {
+        return format!("#{}", input);
+    }
+
+    let name_len = input
+        .find(|c: char| !(c.is_ascii_alphabetic() || c == '-'))
+        .unwrap_or(input.len());
+    let (name, rest) = input.split_at(name_len);
+    let rest = rest.trim();
+    let args = match rest.strip_prefix('(').and_then(|r| r.strip_suffix(')')) {
+        Some(args) => args,
+        None if !name.is_empty() && !rest.is_empty() && name_len < input.len() => rest,
+        None => return input.to_string(),
+    };
+
+    let (args, alpha) = match args.split_once('/') {
+        Some((args, alpha)) => (args, Some(alpha.trim())),
+        None => (args, None),
+    };
+    let mut args: Vec<&str> = args
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|arg| !arg.is_empty())
+        .collect();
+    let components = match name.to_ascii_lowercase().as_str() {
+        "cmyk" | "device-cmyk" | "color" => 4,
+        _ => 3,
+    };
+    let alpha = match alpha {
+        None if args.len() == components + 1 => args.pop(),
+        alpha => alpha,
+    };
+
+    match alpha {
+        Some(alpha) => format!("{}({} / {})", name, args.join(" "), alpha),
+        None => format!("{}({})", name, args.join(" ")),
+    }
+}
+
+/// Any colour, in any of the notations `parse_color` accepts.
+fn any_color(input: &str) -> IResult<&str, Color> {
+    alt((
+        typed_color(ColorType::Hsl),
+        typed_color(ColorType::Rgb),
+        typed_color(ColorType::Hwb),
+        typed_color(ColorType::Hsv),
+        typed_color(ColorType::Lab),
+        typed_color(ColorType::Lch),
+        typed_color(ColorType::Oklab),
+        typed_color(ColorType::Oklch),
+        typed_color(ColorType::Cmyk),
+        typed_color(ColorType::Ycbcr),
+        map(
+            alt((color_function, relative_color_function, xyz_color)),
+            space_color,
+        ),
+        map(hex_color, hex_to_color),
+        typed_color(ColorType::Name),
+        current_color,
+        map(ansi_color, Color::from_ansi),
+    ))(input)
+}
+
+/// A colour packed into an integer, as `0xRRGGBB`, `0xRRGGBBAA` or the decimal
+/// value of `0xRRGGBB`. Only accepted as the whole input, since plain numbers are
+/// rarely colours in running text.
+fn packed_color(input: &str) -> IResult<&str, Color> {
+    alt((
+        map_opt(
+            preceded(tag("0x"), take_while1(|c: char| c.is_ascii_hexdigit())),
+            |digits: &str| {
+                let value = u32::from_str_radix(digits, 16).ok()?;
+                match digits.len() {
+                    6 => Some(Color::from_u32_rgb(value)),
+                    8 => Some(Color::from_u32_rgba(value)),
+                    _ => None,
+                }
+            },
+        ),
+        packed_decimal,
+    ))(input)
+}
+
+/// The decimal value of `0xRRGGBB`. Digits that make up the whole input but are too
+/// large fail outright, rather than reporting the units they might have been missing.
+fn packed_decimal(input: &str) -> IResult<&str, Color> {
+    let (rest, digits) = terminated(digit1, eof)(input)?;
+
+    match digits.parse().ok().filter(|value| *value <= 0xFFFFFF) {
+        Some(value) => Ok((rest, Color::from_u32_rgb(value))),
+        None => Err(nom::Err::Failure(ParseError {
+            input,
+            expected: vec!["a packed colour no larger than 16777215 (0xFFFFFF)".to_string()],
+        })),
+    }
+}
+
+/// A colour temperature such as `6500K`, within the range it can be approximated.
+fn kelvin_color(input: &str) -> IResult<&str, Color> {
+    map(
+        verify(terminated(number, tag("K")), |kelvin| {
+            (1000.0..=15000.0).contains(kelvin)
+        }),
+        Color::from_kelvin,
+    )(input)
+}
+
+/// The wavelength of a visible spectral colour, such as `550nm`.
+fn wavelength_color(input: &str) -> IResult<&str, Color> {
+    map(
+        verify(terminated(number, tag("nm")), |nanometres| {
+            (380.0..=780.0).contains(nanometres)
+        }),
+        Color::from_wavelength,
+    )(input)
+}
+
+pub fn parse_color(input: &str) -> IResult<&str, Color> {
+    terminated(
+        alt((any_color, kelvin_color, wavelength_color, packed_color)),
+        eof,
+    )(input)
+}
+
+/// The functions CSS writes colours with, which `find_colors` looks for.
+const CSS_FUNCTIONS: &[&str] = &[
+    "rgb",
+    "rgba",
+    "hsl",
+    "hsla",
+    "hwb",
+    "lab",
+    "lch",
+    "oklab",
+    "oklch",
+    "color",
+    "device-cmyk",
+];
+
+/// A colour literal as CSS writes it: a hex code, named colour or CSS colour
+/// function. Other notations, such as `hsv()` or `brightred`, are rarely colours in
+/// running text, and `currentcolor` isn't a colour on its own.
+fn css_literal(input: &str) -> IResult<&str, Color> {
+    let (_, function) = opt(terminated(
+        take_while1(|c: char| c.is_ascii_alphabetic() || c == '-'),
+        tag("("),
+    ))(input)?;
+
+    match function {
+        Some(name) if !CSS_FUNCTIONS.contains(&name.to_ascii_lowercase().as_str()) => fail(input),
+        Some(_) => any_color(input),
+        None => alt((map(hex_color, hex_to_color), typed_color(ColorType::Name)))(input),
+    }
+}
+
+/// Finds every CSS colour literal in a larger piece of text, such as a stylesheet,
+/// log or SVG file, along with the byte range it was found at. Colours must stand on
+/// their own, so `red` isn't found in `reduce`.
+pub fn find_colors(input: &str) -> Vec<(Range<usize>, Color)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+    let mut colors = Vec::new();
+    let mut start = 0;
+
+    while let Some(c) = input[start..].chars().next() {
+        let at_boundary = !input[..start].chars().next_back().is_some_and(is_word);
+        if at_boundary {
+            if let Ok((rest, color)) = css_literal(&input[start..]) {
+                let end = input.len() - rest.len();
+                if !rest.chars().next().is_some_and(is_word) {
+                    colors.push((start..end, color));
+                    start = end;
+                    continue;
+                }
+            }
+        }
+        start += c.len_utf8();
+    }
+
+    colors
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CssKind {
+    Number,
+    Percentage,
+    Angle,
+    None,
+    /// A channel keyword or calculation in relative colour syntax
+    Expression,
+}
+
+/// A single component of a CSS colour function, and where it was in the input.
+#[derive(Debug, Clone, Copy)]
+struct CssValue<'a> {
+    kind: CssKind,
+    value: f32,
+    at: &'a str,
+}
+
+fn css_value(input: &str) -> IResult<&str, CssValue<'_>> {
+    let (rest, (kind, value)) = alt((
+        map(none, |v| (CssKind::None, v)),
+        map(percentage, |v| (CssKind::Percentage, v)),
+        map(
+            terminated(
+                number,
+                alt((tag("deg"), tag("rad"), tag("grad"), tag("turn"))),
+            ),
+            |v| (CssKind::Angle, v),
+        ),
+        map(number, |v| (CssKind::Number, v)),
+    ))(input)?;
+
+    Ok((
+        rest,
+        CssValue {
+            kind,
+            value,
+            at: input,
+        },
+    ))
+}
+
+/// What CSS allows for one component: the range of numbers and percentages, if
+/// they're allowed at all, and whether it's a hue that takes angles.
+#[derive(Clone, Copy)]
+struct CssRule {
+    number: Option<(f32, f32)>,
+    percentage: Option<(f32, f32)>,
+    angle: bool,
 }
 
-fn percentage_to_color_255(input: f32) -> f32 {
-    (input / 100.0) * 255.0
+const ANY: (f32, f32) = (f32::NEG_INFINITY, f32::INFINITY);
+const POSITIVE: (f32, f32) = (0.0, f32::INFINITY);
+const PERCENT: (f32, f32) = (0.0, 100.0);
+
+const fn css_rule(number: Option<(f32, f32)>, percentage: Option<(f32, f32)>) -> CssRule {
+    CssRule {
+        number,
+        percentage,
+        angle: false,
+    }
 }
 
-fn space_separated_percentages(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
-    map(
-        tuple((
-            percentage,
-            space1,
-            percentage,
-            space1,
-            percentage,
-            opt(preceded(delimited(space0, tag("/"), space0), percentage)),
-        )),
-        |(p1, _, p2, _, p3, p4)| {
-            (
-                percentage_to_color_255(p1),
-                percentage_to_color_255(p2),
-                percentage_to_color_255(p3),
-                (p4.map(|x| x / 100.0)),
-            )
-        },
-    )(input)
+const HUE: CssRule = CssRule {
+    number: Some(ANY),
+    percentage: None,
+    angle: true,
+};
+const RGB_CHANNEL: CssRule = css_rule(Some((0.0, 255.0)), Some(PERCENT));
+const HUNDRED: CssRule = css_rule(Some(PERCENT), Some(PERCENT));
+const PERCENT_ONLY: CssRule = css_rule(None, Some(PERCENT));
+const UNBOUNDED: CssRule = css_rule(Some(ANY), Some(ANY));
+const CHROMA: CssRule = css_rule(Some(POSITIVE), Some(POSITIVE));
+const UNIT: CssRule = css_rule(Some((0.0, 1.0)), Some(PERCENT));
+
+fn css_failure(at: &str, expected: String) -> nom::Err<ParseError<&str>> {
+    nom::Err::Failure(ParseError {
+        input: at,
+        expected: vec![expected],
+    })
 }
 
-fn comma_separated_floats(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
-    map(
+fn check_css_value<'a>(
+    value: &CssValue<'a>,
+    rule: &CssRule,
+    legacy: bool,
+) -> Result<(), nom::Err<ParseError<&'a str>>> {
+    let range = match value.kind {
+        CssKind::None if !legacy => return Ok(()),
+        // These depend on the origin colour, so the regular parser checks them
+        CssKind::Expression => return Ok(()),
+        CssKind::Angle if rule.angle => return Ok(()),
+        CssKind::Number => rule.number,
+        CssKind::Percentage => rule.percentage,
+        _ => None,
+    };
+
+    match range {
+        Some((min, max)) if (min..=max).contains(&value.value) => Ok(()),
+        Some((min, max)) => {
+            let unit = if value.kind == CssKind::Percentage {
+                "%"
+            } else {
+                ""
+            };
+            let expected = match (min.is_finite(), max.is_finite()) {
+                (true, true) => format!("a value from {}{} to {}{}", min, unit, max, unit),
+                _ => format!("a value of at least {}{}", min, unit),
+            };
+            Err(css_failure(value.at, expected))
+        }
+        None => {
+            let allowed = [
+                (rule.number.is_some(), "a number"),
+                (rule.percentage.is_some(), "a percentage"),
+                (rule.angle, "an angle"),
+                (!legacy, "'none'"),
+            ];
+            let allowed: Vec<String> = allowed
+                .iter()
+                .filter(|(ok, _)| *ok)
+                .map(|(_, name)| name.to_string())
+                .collect();
+            Err(css_failure(value.at, one_of(&allowed)))
+        }
+    }
+}
+
+/// The arguments of a colour function in legacy comma syntax, or modern space
+/// syntax with an optional `/ alpha`. Returns whether the legacy syntax was used.
+fn css_arguments(input: &str) -> IResult<&str, (Vec<CssValue<'_>>, Option<CssValue<'_>>, bool)> {
+    let comma = || delimited(multispace0, tag(","), multispace0);
+    let legacy = map(
+        tuple((css_value, many1(preceded(comma(), css_value)))),
+        |(first, rest)| {
+            let values = std::iter::once(first).chain(rest).collect();
+            (values, None, true)
+        },
+    );
+    let modern = map(
         tuple((
-            float,
-            delimited(space0, tag(","), space0),
-            float,
-            delimited(space0, tag(","), space0),
-            float,
-            opt(preceded(delimited(space0, tag(","), space0), float)),
+            separated_list1(multispace1, css_value),
+            opt(preceded(
+                delimited(multispace0, tag("/"), multispace0),
+                css_value,
+            )),
         )),
-        |(p1, _, p2, _, p3, p4)| (p1, p2, p3, p4),
-    )(input)
+        |(values, alpha)| (values, alpha, false),
+    );
+
+    delimited(multispace0, alt((legacy, modern)), multispace0)(input)
 }
 
-fn space_separated_floats(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
-    map(
+/// A channel keyword, such as `r`, or a bracketed calculation, such as
+/// `calc(h + 30)`, in relative colour syntax.
+fn css_expression(input: &str) -> IResult<&str, CssValue<'_>> {
+    let expression = |rest: &str| CssValue {
+        kind: CssKind::Expression,
+        value: 0.0,
+        at: &input[..input.len() - rest.len()],
+    };
+    let (rest, _) = opt(alpha1)(input)?;
+    if !rest.starts_with('(') {
+        let (rest, _) = alpha1(input)?;
+        return Ok((rest, expression(rest)));
+    }
+
+    let mut depth = 0;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 1 => {
+                let rest = &rest[i + 1..];
+                return Ok((rest, expression(rest)));
+            }
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    Err(css_failure(rest, "')'".to_string()))
+}
+
+/// The arguments of relative colour syntax, which are always space separated.
+/// Literal values are checked like any other, and expressions are left alone.
+fn css_relative_arguments(input: &str) -> IResult<&str, (Vec<CssValue<'_>>, Option<CssValue<'_>>)> {
+    fn argument(input: &str) -> IResult<&str, CssValue<'_>> {
+        alt((
+            terminated(css_value, peek(alt((multispace1, tag("/"), tag(")"))))),
+            css_expression,
+        ))(input)
+    }
+
+    delimited(
+        multispace0,
         tuple((
-            float,
-            space1,
-            float,
-            space1,
-            float,
-            opt(preceded(delimited(space0, tag("/"), space0), float)),
+            separated_list1(multispace1, argument),
+            opt(preceded(
+                delimited(multispace0, tag("/"), multispace0),
+                argument,
+            )),
         )),
-        |(p1, _, p2, _, p3, p4)| (p1, p2, p3, p4),
+        multispace0,
     )(input)
 }
 
-fn rgb_values(input: &str) -> IResult<&str, (f32, f32, f32, Option<f32>)> {
-    // Combine the above with alpha values
-    alt((
-        comma_separated_percentages,
-        comma_separated_floats,
-        space_separated_percentages,
-        space_separated_floats,
-    ))(input)
+/// A CSS colour function, checked against the CSS grammar and ranges.
+fn css_function(input: &str) -> IResult<&str, ()> {
+    let (rest, name) = terminated(
+        take_while1(|c: char| c.is_ascii_alphabetic() || c == '-'),
+        tag("("),
+    )(input)?;
+    let name = name.to_ascii_lowercase();
+    let name = name.as_str();
+
+    let (rest, origin) = opt(delimited(
+        multispace0,
+        preceded(terminated(tag("from"), multispace1), css_color),
+        multispace1,
+    ))(rest)?;
+
+    let (legacy_allowed, rules): (bool, &[CssRule]) = match name {
+        "rgb" | "rgba" => (true, &[RGB_CHANNEL; 3]),
+        "hsl" | "hsla" => (true, &[HUE, HUNDRED, HUNDRED]),
+        "hwb" => (false, &[HUE, HUNDRED, HUNDRED]),
+        "lab" => (false, &[HUNDRED, UNBOUNDED, UNBOUNDED]),
+        "lch" => (false, &[HUNDRED, CHROMA, HUE]),
+        "oklab" => (false, &[UNIT, UNBOUNDED, UNBOUNDED]),
+        "oklch" => (false, &[UNIT, CHROMA, HUE]),
+        "color" => (false, &[UNBOUNDED; 3]),
+        "device-cmyk" => (true, &[UNIT; 4]),
+        _ => return Err(css_failure(input, "a CSS colour function".to_string())),
+    };
+
+    let (rest, _) = if name == "color" {
+        preceded(multispace0, terminated(color_space, multispace1))(rest)?
+    } else {
+        (rest, ColorSpace::Srgb)
+    };
+    let (rest, (mut values, mut alpha, legacy)) = match origin {
+        Some(()) => map(css_relative_arguments, |(values, alpha)| {
+            (values, alpha, false)
+        })(rest)?,
+        None => css_arguments(rest)?,
+    };
+
+    if legacy && !legacy_allowed {
+        let first = values[0].at;
+        let comma = &first[first.find(',').unwrap_or(0)..];
+        return Err(css_failure(comma, "a space".to_string()));
+    }
+    if legacy && values.len() == rules.len() + 1 {
+        alpha = values.pop();
+    }
+    if values.len() < rules.len() {
+        return Err(css_failure(rest, format!("{} components", rules.len())));
+    }
+    if values.len() > rules.len() {
+        return Err(css_failure(values[rules.len()].at, "')'".to_string()));
+    }
+
+    for (i, (value, rule)) in values.iter().zip(rules).enumerate() {
+        // Legacy hsl() needs percentages for saturation and lightness
+        let rule = if legacy && name.starts_with("hsl") && i > 0 {
+            &PERCENT_ONLY
+        } else {
+            rule
+        };
+        check_css_value(value, rule, legacy)?;
+    }
+    if let Some(alpha) = alpha {
+        check_css_value(&alpha, &UNIT, legacy)?;
+    }
+
+    // rgb() can't mix numbers and percentages
+    if name.starts_with("rgb") {
+        let mut kinds = values
+            .iter()
+            .filter(|v| !matches!(v.kind, CssKind::None | CssKind::Expression));
+        if let Some(first) = kinds.next() {
+            if let Some(other) = kinds.find(|v| v.kind != first.kind) {
+                let expected = match first.kind {
+                    CssKind::Percentage => "a percentage, like the other channels",
+                    _ => "a number, like the other channels",
+                };
+                return Err(css_failure(other.at, expected.to_string()));
+            }
+        }
+    }
+
+    map(tag(")"), |_| ())(rest)
 }
 
-fn rgb_color(input: &str) -> IResult<&str, (f32, f32, f32, f32)> {
-    let (input, (red, green, blue, alpha)) = preceded(
-        alt((tag("rgba"), tag("rgb"))),
-        delimited(tag("("), rgb_values, tag(")")),
-    )(input)?;
+/// Parses a colour only if it's valid CSS, which is stricter than `parse_color`:
+/// CSS notations only, no mixing legacy and modern syntax, and values must be in
+/// the ranges CSS would otherwise clamp them to.
+pub fn parse_color_strict(input: &str) -> IResult<&str, Color> {
+    terminated(css_color, eof)(input)?;
 
-    Ok((input, (red, green, blue, alpha.unwrap_or(1.0))))
+    parse_color(input)
 }
 
-pub fn parse_color(input: &str) -> IResult<&str, Color> {
-    terminated(
-        alt((
-            map(hsl_color, |(hue, sat, lum, alpha)| {
-                Color::from_hsl(hue, sat, lum, alpha)
-            }),
-            map(rgb_color, |(red, green, blue, alpha)| {
-                Color::from_hsl(red, green, blue, alpha)
-            }),
-        )),
-        eof,
-    )(input)
+/// Any colour CSS accepts, checked but not parsed.
+fn css_color(input: &str) -> IResult<&str, ()> {
+    alt((
+        map(hex_color, |_| ()),
+        map(named_color, |_| ()),
+        map(current_color, |_| ()),
+        css_function,
+    ))(input)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn parse_angle() {
+        let cases = [
+            ("90", Angle::Degrees(90.0)),
+            ("90deg", Angle::Degrees(90.0)),
+            ("1.5rad", Angle::Radians(1.5)),
+            ("100grad", Angle::Gradians(100.0)),
+            ("0.25turn", Angle::Turns(0.25)),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(angle(input).unwrap(), ("", expected));
+        }
+
+        assert_eq!(Angle::Degrees(90.0).to_degrees(), 90.0);
+        assert_eq!(
+            Angle::Radians(std::f32::consts::FRAC_PI_2).to_degrees(),
+            90.0
+        );
+        assert_eq!(Angle::Gradians(100.0).to_degrees(), 90.0);
+        assert_eq!(Angle::Turns(0.25).to_degrees(), 90.0);
+        assert_eq!(Angle::Gradians(400.0).to_degrees(), 360.0);
+
+        let color = parse_color("hsl(100grad 50% 50%)").unwrap().1;
+        assert_eq!(color.hsl().0, 90.0);
+    }
+
     #[test]
     fn parse_hsl_values() {
         let (rest, output) = hsl_values("32,11.22,04oeeooe").unwrap();
@@ -337,4 +1715,583 @@ mod test {
         assert!(rgb_values("32, 2%, 225 / 1").is_err());
         assert!(rgb_values("32%, 2%, 225, 44%").is_err());
     }
+
+    #[test]
+    fn parse_hex() {
+        let (_, color) = hex_color("#ff0033").unwrap();
+        assert_eq!(color, (255.0, 0.0, 51.0, 1.0));
+
+        let (_, color) = hex_color("#F03").unwrap();
+        assert_eq!(color, (255.0, 0.0, 51.0, 1.0));
+
+        let (_, color) = hex_color("#ff003380").unwrap();
+        assert_eq!(color, (255.0, 0.0, 51.0, 128.0 / 255.0));
+
+        let (_, color) = hex_color("#f038").unwrap();
+        assert_eq!(color, (255.0, 0.0, 51.0, 136.0 / 255.0));
+
+        // Only 3, 4, 6 and 8 digit forms are valid
+        assert!(hex_color("#ff00f").is_err());
+        assert!(hex_color("#ff00f0f").is_err());
+        assert!(hex_color("ff0033").is_err());
+        assert!(parse_color("#ff0033ff00").is_err());
+    }
+
+    #[test]
+    fn parse_named() {
+        let (_, color) = named_color("rebeccapurple").unwrap();
+        assert_eq!(color, (102.0, 51.0, 153.0, 1.0));
+
+        let (_, color) = named_color("ReD").unwrap();
+        assert_eq!(color, (255.0, 0.0, 0.0, 1.0));
+
+        assert!(named_color("blurple").is_err());
+        assert!(parse_color("red2").is_err());
+    }
+
+    #[test]
+    fn parse_hwb() {
+        let (_, color) = hwb_color("hwb(120 30% 40%)").unwrap();
+        assert_eq!(color, (120.0, 30.0, 40.0, 1.0));
+
+        let (_, color) = hwb_color("hwb( 0.5turn 30 40 / 50%)").unwrap();
+        assert_eq!(color, (180.0, 30.0, 40.0, 0.5));
+
+        let (_, color) = hwb_color("hwb(120deg 30% 40% / 0.2)").unwrap();
+        assert_eq!(color, (120.0, 30.0, 40.0, 0.2));
+
+        // hwb() has no legacy comma syntax
+        assert!(hwb_color("hwb(120, 30%, 40%)").is_err());
+    }
+
+    #[test]
+    fn parse_lab() {
+        let (_, color) = lab_color("lab(52% 40 59)").unwrap();
+        assert_eq!(color, (52.0, 40.0, 59.0, 1.0));
+
+        let (_, color) = lab_color("lab(52 -40% 100% / 0.5)").unwrap();
+        assert_eq!(color, (52.0, -50.0, 125.0, 0.5));
+
+        assert!(lab_color("lab(52%, 40, 59)").is_err());
+    }
+
+    #[test]
+    fn parse_lch() {
+        let (_, color) = lch_color("lch(52% 72 50)").unwrap();
+        assert_eq!(color, (52.0, 72.0, 50.0, 1.0));
+
+        let (_, color) = lch_color("lch(52% 50% 0.5turn / 20%)").unwrap();
+        assert_eq!(color, (52.0, 75.0, 180.0, 0.2));
+
+        assert!(lch_color("lch(52%, 72, 50)").is_err());
+    }
+
+    #[test]
+    fn parse_oklab() {
+        let (_, color) = oklab_color("oklab(0.628 0.225 0.126)").unwrap();
+        assert_eq!(color, (0.628, 0.225, 0.126, 1.0));
+
+        let (_, color) = oklab_color("oklab(50% 100% -50% / 0.5)").unwrap();
+        assert_eq!(color, (0.5, 0.4, -0.2, 0.5));
+    }
+
+    #[test]
+    fn parse_oklch() {
+        let (_, color) = oklch_color("oklch(62.8% 0.258 29.2deg)").unwrap();
+        assert_eq!(color, (0.628, 0.258, 29.2, 1.0));
+
+        let (_, color) = oklch_color("oklch(0.5 50% 180 / 25%)").unwrap();
+        assert_eq!(color, (0.5, 0.2, 180.0, 0.25));
+
+        assert!(oklch_color("oklch(0.5, 0.2, 180)").is_err());
+    }
+
+    #[test]
+    fn parse_out_of_gamut() {
+        use crate::colors::GamutMapping;
+
+        let p3_red = parse_color("color(display-p3 1 0 0)").unwrap().1;
+        assert!(!p3_red.in_srgb_gamut());
+        let mapped = p3_red.gamut_map(GamutMapping::ReduceChroma);
+        assert!(mapped.in_srgb_gamut());
+        assert_eq!("#FF0B0C", mapped.hex_string());
+        assert_eq!(28.9, mapped.oklch().2);
+        assert_eq!("color(display-p3 0.918 0.211 0.154)", mapped.to_string());
+        assert_eq!("#FF0000", p3_red.gamut_map(GamutMapping::Clip).hex_string());
+
+        // Clipping pulls this green towards yellow, mapping keeps it close to 150°
+        let green = parse_color("oklch(0.7 0.3 150)").unwrap().1;
+        assert!(!green.in_srgb_gamut());
+        assert_eq!(142.5, green.gamut_map(GamutMapping::Clip).oklch().2);
+        let mapped = green.gamut_map(GamutMapping::ReduceChroma);
+        assert_eq!("oklch(0.709 0.21 147.1)", mapped.to_string());
+
+        let muted = parse_color("oklch(0.5 0.1 150)").unwrap().1;
+        assert!(muted.in_srgb_gamut());
+        assert_eq!(
+            muted.oklch(),
+            muted.gamut_map(GamutMapping::ReduceChroma).oklch()
+        );
+    }
+
+    #[test]
+    fn parse_color_function() {
+        let (_, color) = color_function("color(display-p3 1 0 0.33)").unwrap();
+        assert_eq!(color, (ColorSpace::DisplayP3, 1.0, 0.0, 0.33, 1.0));
+
+        let (_, color) = color_function("color( srgb-linear 50% 0.2 1 / 0.5 )").unwrap();
+        assert_eq!(color, (ColorSpace::SrgbLinear, 0.5, 0.2, 1.0, 0.5));
+
+        let (_, color) = color_function("color(xyz 0.2 0.3 0.4)").unwrap();
+        assert_eq!(color, (ColorSpace::XyzD65, 0.2, 0.3, 0.4, 1.0));
+
+        assert!(color_function("color(cmyk 1 0 0)").is_err());
+        assert!(color_function("color(srgb 1, 0, 0)").is_err());
+
+        // Components outside of sRGB are kept, rather than clamped
+        for input in [
+            "color(display-p3 1 0 0)",
+            "color(rec2020 0 1 0 / 0.5)",
+            "color(srgb-linear 1.2 -0.1 0.5)",
+            "color(xyz-d50 0.9 1 0.7)",
+        ] {
+            assert_eq!(parse_color(input).unwrap().1.to_string(), input);
+        }
+        let p3_red = parse_color("color(display-p3 1 0 0)").unwrap().1;
+        assert_eq!(p3_red.hex_string(), "#FF0000");
+        assert_eq!(p3_red.oklch(), (0.649, 0.299, 29.0, 1.0));
+    }
+
+    #[test]
+    fn parse_as_type() {
+        let (_, color) = parse_color_as("010", ColorType::Hex).unwrap();
+        assert_eq!(color.rgb(), (0.0, 17.0, 0.0, 1.0));
+
+        let (_, color) = parse_color_as("#010", ColorType::Hex).unwrap();
+        assert_eq!(color.rgb(), (0.0, 17.0, 0.0, 1.0));
+
+        let (_, color) = parse_color_as(
+            "color(rec2020 1 1 1)",
+            ColorType::Color(ColorSpace::Rec2020),
+        )
+        .unwrap();
+        assert_eq!(color.rgb(), (255.0, 255.0, 255.0, 1.0));
+
+        assert!(parse_color_as("010", ColorType::Name).is_err());
+        assert!(parse_color_as("rgb(1 2 3)", ColorType::Hsl).is_err());
+        assert!(parse_color_as("#010", ColorType::Rgb).is_err());
+        assert!(
+            parse_color_as("color(srgb 1 1 1)", ColorType::Color(ColorSpace::Rec2020)).is_err()
+        );
+        assert!(parse_color_as("#010", ColorType::Office).is_err());
+    }
+
+    #[test]
+    fn parse_cmyk() {
+        let (_, color) = cmyk_color("cmyk(0%, 81%, 81%, 30%)").unwrap();
+        assert_eq!(color, (0.0, 81.0, 81.0, 30.0, 1.0));
+
+        let (_, color) = cmyk_color("device-cmyk(0 0.5 0.25 1 / 50%)").unwrap();
+        assert_eq!(color, (0.0, 50.0, 25.0, 100.0, 0.5));
+
+        let (_, color) = cmyk_color("device-cmyk(0%, 50%, 25%, 100%, 0.5)").unwrap();
+        assert_eq!(color, (0.0, 50.0, 25.0, 100.0, 0.5));
+
+        // Can't mix and match separators
+        assert!(cmyk_color("cmyk(0% 81%, 81% 30%)").is_err());
+        assert!(cmyk_color("cmyk(0% 81% 81%)").is_err());
+    }
+
+    #[test]
+    fn parse_hsv() {
+        let (_, color) = hsv_color("hsv(210, 66.7%, 60%)").unwrap();
+        assert_eq!(color, (210.0, 66.7, 60.0, 1.0));
+
+        let (_, color) = hsv_color("hsb(0.5turn 50 50 / 0.5)").unwrap();
+        assert_eq!(color, (180.0, 50.0, 50.0, 0.5));
+
+        let (_, color) = hsv_color("hsva(10, 20, 30, 0.4)").unwrap();
+        assert_eq!(color, (10.0, 20.0, 30.0, 0.4));
+    }
+
+    #[test]
+    fn parse_none() {
+        let (_, color) = hsl_color("hsl(none 50% 50%)").unwrap();
+        assert!(color.0.is_nan());
+        assert_eq!((color.1, color.2, color.3), (50.0, 50.0, 1.0));
+
+        let (_, color) = rgb_color("rgb(255 none 0 / none)").unwrap();
+        assert!(color.1.is_nan() && color.3.is_nan());
+
+        let (_, color) = rgb_color("rgb(100% none 0%)").unwrap();
+        assert!(color.1.is_nan());
+
+        let (_, color) = oklch_color("oklch(none 0.2 none)").unwrap();
+        assert!(color.0.is_nan() && color.2.is_nan());
+
+        let (_, color) = color_function("color(display-p3 1 none 0)").unwrap();
+        assert!(color.2.is_nan());
+
+        // The legacy comma syntax doesn't allow none
+        assert!(hsl_color("hsl(none, 50%, 50%)").is_err());
+        assert!(rgb_color("rgb(255, none, 0)").is_err());
+
+        let color = parse_color("rgb(255 none 0)").unwrap().1;
+        assert_eq!("rgb(255 none 0)", color.to_string());
+        let color = parse_color("device-cmyk(none 50% 0% 0% / none)").unwrap().1;
+        assert_eq!("device-cmyk(none 50% 0% 0% / none)", color.to_string());
+
+        // Only `none` is missing. NaN and infinity aren't numbers
+        for input in [
+            "rgb(NaN 0 0)",
+            "lab(nan 0 0)",
+            "cmyk(nan 0 0 0)",
+            "hsl(inf 50% 50%)",
+            "rgb(1e39 0 0)",
+            "color(display-p3 1 infinity 0)",
+        ] {
+            assert!(parse_color(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_relative() {
+        let color = parse_color("rgb(from #ff0000 r g b / 0.5)").unwrap().1;
+        assert_eq!(color.rgb(), (255.0, 0.0, 0.0, 0.5));
+
+        let color = parse_color("rgb(from red b g r)").unwrap().1;
+        assert_eq!(color.rgb(), (0.0, 0.0, 255.0, 1.0));
+
+        let color = parse_color("hsl(from tomato h s calc(l - 10%))").unwrap().1;
+        assert_eq!(color.to_string(), "hsl(9 100 53.9)");
+
+        let color = parse_color("hsl(from tomato calc(h + 180) s l)").unwrap().1;
+        assert_eq!(color.hsl().0, 189.0);
+
+        let color = parse_color("rgb(from rgb(0 0 0 / 0.5) calc(50% + r) 0 0)")
+            .unwrap()
+            .1;
+        assert_eq!(color.rgb(), (128.0, 0.0, 0.0, 0.5));
+
+        let color = parse_color("lch(from blue l c h / calc(alpha / 2))")
+            .unwrap()
+            .1;
+        assert_eq!(color.rgb().3, 0.5);
+
+        let color = parse_color("color(from white xyz-d65 x y z)").unwrap().1;
+        assert_eq!(color.rgb(), (255.0, 255.0, 255.0, 1.0));
+
+        // Only the channels of the function being used are in scope
+        assert!(parse_color("rgb(from red h s l)").is_err());
+        assert!(parse_color("rgb(from red r g)").is_err());
+        // Hue doesn't take percentages
+        assert!(parse_color("hsl(from red 10% s l)").is_err());
+
+        for input in [
+            "hsl(from red calc(h / 0) s l)",
+            "rgb(from red calc((r - r) / 0) g b)",
+            "rgb(from red calc(r * 1e38 * 10) g b)",
+        ] {
+            let error = input.parse::<Color>().unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .contains("a calculation with a finite result"),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |input: &str| match input.parse::<Color>() {
+            Err(crate::Error::InvalidColor {
+                offset,
+                column,
+                found,
+                expected,
+                ..
+            }) => (offset, column, found, one_of(&expected)),
+            other => panic!("expected InvalidColor, got {:?}", other),
+        };
+
+        assert_eq!(
+            error("rgb(255 0 0"),
+            (
+                11,
+                12,
+                "the end of the input".to_string(),
+                "')'".to_string()
+            )
+        );
+        assert_eq!(
+            error("rgb(255 0 0)x"),
+            (
+                12,
+                13,
+                "`x`".to_string(),
+                "the end of the colour".to_string()
+            )
+        );
+        assert_eq!(
+            error("lab(50 abc 0)"),
+            (7, 8, "`abc`".to_string(), "a number or 'none'".to_string())
+        );
+        assert_eq!(
+            error("hsl(120, 50; 50%)"),
+            (11, 12, "`;`".to_string(), "','".to_string())
+        );
+        assert_eq!(
+            error("foo(1)"),
+            (0, 1, "`foo`".to_string(), "a colour".to_string())
+        );
+        // Columns count characters, offsets count bytes
+        assert_eq!(error("rgb(é)").0, 4);
+        assert_eq!(error("rgb(0 0 0é)").1, 10);
+    }
+
+    #[test]
+    fn parse_case_and_whitespace() {
+        let green = parse_color("hsl(120, 50%, 50%)").unwrap().1;
+        for input in [
+            "HSL(120, 50%, 50%)",
+            "Hsla(120DEG, 50%, 50%)",
+            "hsl(\n\t120,\n\t50%,\n\t50%\n)",
+            "hsl(120\t50%\t50%)",
+        ] {
+            assert_eq!(
+                parse_color(input).unwrap().1.rgb(),
+                green.rgb(),
+                "{:?}",
+                input
+            );
+        }
+
+        assert_eq!(
+            parse_color("Rgb( 1, 2, 3 )").unwrap().1.rgb(),
+            (1.0, 2.0, 3.0, 1.0)
+        );
+        assert_eq!(
+            parse_color("RGB(From Red R\nG B / Alpha)").unwrap().1.rgb(),
+            (255.0, 0.0, 0.0, 1.0)
+        );
+        assert_eq!(
+            parse_color("RGB(1 2 3 / NONE)").unwrap().1.to_string(),
+            "rgb(1 2 3 / none)"
+        );
+        assert_eq!(
+            parse_color("COLOR(Display-P3\n1 0 0)").unwrap().1,
+            parse_color("color(display-p3 1 0 0)").unwrap().1
+        );
+    }
+
+    #[test]
+    fn parse_strict() {
+        for input in [
+            "rgb(255 0 0)",
+            "rgba(255, 0, 0, 0.5)",
+            "rgb(100% 0% 0% / 50%)",
+            "rgb(none 0 0)",
+            "hsl(120deg 50 50 / 0.5)",
+            "hsl(120, 50%, 50%)",
+            "hwb(120 20% 30%)",
+            "lab(50 -20 30)",
+            "oklch(0.5 0.1 30)",
+            "color(display-p3 1 0 0)",
+            "device-cmyk(0 1 1 0)",
+            "rgb(255 0 0 / 50%)",
+            "rgba(255, 0, 0, 50%)",
+            "rgb(100% 0% 0% / 0.5)",
+            "rgb(from red r g b)",
+            "rgb(from red 255 g b / 0.5)",
+            "hsl(from red calc(h + 30) s l / 50%)",
+            "color(from red display-p3 r 0.5 b)",
+            "RGB(255 0 0)",
+            "Hsl(120DEG 50% 50%)",
+            "#ff0000",
+            "tomato",
+        ] {
+            assert!(parse_color_strict(input).is_ok(), "{}", input);
+        }
+
+        for input in [
+            // Not CSS
+            "hsv(120 50% 50%)",
+            "cmyk(0 1 1 0)",
+            // Mixed syntax and units
+            "rgb(255 50% 0)",
+            "rgb(255, 0, 0 / 0.5)",
+            "rgb(none, 0, 0)",
+            "hsl(120, 50, 50%)",
+            "hwb(120, 20%, 30%)",
+            "rgb(10deg 0 0)",
+            "hsl(10% 50% 50%)",
+            // Out of range
+            "rgb(256 0 0)",
+            "rgb(0 0 0 / 1.5)",
+            "hsl(120 101% 50%)",
+            "lch(50 -1 30)",
+            "oklab(1.2 0 0)",
+            // Relative colours' literal values and origins are checked too
+            "rgb(from red 300 g b)",
+            "hsl(from red h 200% l)",
+            "rgb(from red r g b / 2)",
+            "rgb(from hsv(0 100% 100%) r g b)",
+            // Wrong number of components
+            "rgb(1 2)",
+            "rgb(1 2 3 4)",
+        ] {
+            assert!(parse_color_strict(input).is_err(), "{}", input);
+        }
+
+        for input in [
+            "rgb(255 0 0 / 50%)",
+            "rgba(255, 0, 0, 50%)",
+            "rgb(100% 0% 0% / 0.5)",
+        ] {
+            let color = Color::from_str_strict(input).unwrap();
+            assert_eq!((255.0, 0.0, 0.0, 0.5), color.rgb(), "{}", input);
+        }
+        assert!(matches!(
+            Color::from_str_strict("rgb(from red 300 g b)"),
+            Err(crate::Error::InvalidColor { column: 14, .. })
+        ));
+
+        assert!(matches!(
+            Color::from_str_strict("rgb(255 300 0)"),
+            Err(crate::Error::InvalidColor { column: 9, .. })
+        ));
+    }
+
+    #[test]
+    fn find_colors_in_text() {
+        let css = ".a { color: #ff0000; background: rgb(0 0 255 / 0.5) }\n\
+                   .b { border: 1px solid tomato; transition: reduce 1s }";
+        let found: Vec<_> = find_colors(css)
+            .into_iter()
+            .map(|(range, color)| (&css[range], color.to_string()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("#ff0000", "#FF0000".to_string()),
+                ("rgb(0 0 255 / 0.5)", "rgb(0 0 255 / 0.5)".to_string()),
+                ("tomato", "tomato".to_string()),
+            ]
+        );
+
+        let svg = r##"<rect fill="#0f0" stroke="hsl(0, 100%, 50%)"/>"##;
+        let ranges: Vec<_> = find_colors(svg).into_iter().map(|(r, _)| r).collect();
+        assert_eq!(ranges, [12..16, 26..43]);
+
+        // Not colours on their own
+        assert!(find_colors("redirect #abcdefgh my-red #12345").is_empty());
+
+        // Only CSS colour literals
+        let text = "fill: currentcolor; log: brightred ansi:208 hsv(0 100% 100%) \
+                    cmyk(0 1 1 0) xyz(0.2 0.3 0.4) ycbcr(128 128 128)";
+        assert!(find_colors(text).is_empty(), "{:?}", find_colors(text));
+        let text = "Device-CMYK(0 1 1 0) color(display-p3 1 0 0) LAB(50 0 0)";
+        assert_eq!(3, find_colors(text).len());
+    }
+
+    #[test]
+    fn parse_packed() {
+        let orange = Color::from_rgb(255.0, 136.0, 0.0, 1.0);
+        assert_eq!(parse_color("0xFF8800").unwrap().1, orange);
+        assert_eq!(parse_color("0xff8800").unwrap().1, orange);
+        assert_eq!(parse_color("16746496").unwrap().1, orange);
+        assert_eq!(parse_color("0").unwrap().1.rgb(), (0.0, 0.0, 0.0, 1.0));
+        assert_eq!(parse_color("0xFF880000").unwrap().1.rgb().3, 0.0);
+
+        assert!(parse_color("0xFF88").is_err());
+        for input in ["16777216", "4294967295", "99999999999999999999"] {
+            let error = input.parse::<Color>().unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "Could not parse colour: expected a packed colour no larger than \
+                     16777215 (0xFFFFFF) at column 1, found `{}`",
+                    input
+                )
+            );
+        }
+        assert!(find_colors("font-weight: 400").is_empty());
+    }
+
+    #[test]
+    fn parse_ansi() {
+        let color = parse_color("ansi:208").unwrap().1;
+        assert_eq!(color.rgb(), (255.0, 135.0, 0.0, 1.0));
+        assert_eq!(color.to_string(), "ansi:208");
+
+        let color = parse_color("brightMagenta").unwrap().1;
+        assert_eq!(color.to_string(), "ansi:13");
+        assert_eq!(parse_color("ansi:red").unwrap().1.rgb().0, 205.0);
+
+        // Plain basic names are still CSS colours
+        assert_eq!(parse_color("red").unwrap().1.rgb().0, 255.0);
+        assert_eq!(
+            parse_color_as("red", ColorType::Ansi).unwrap().1.rgb().0,
+            205.0
+        );
+        assert_eq!(
+            parse_color_as("208", ColorType::Ansi).unwrap().1.rgb().1,
+            135.0
+        );
+
+        assert!(parse_color("ansi:256").is_err());
+        assert!(parse_color("brightorange").is_err());
+    }
+
+    #[test]
+    fn parse_kelvin() {
+        let color = parse_color("6500K").unwrap().1;
+        assert_eq!(color, Color::from_kelvin(6500.0));
+        assert_eq!(parse_color("2700k").unwrap().1.to_string(), "#FFAD58");
+
+        assert!(parse_color("500K").is_err());
+        assert!(parse_color("20000K").is_err());
+    }
+
+    #[test]
+    fn parse_ycbcr() {
+        let (_, color) = ycbcr_color("ycbcr(63 102.3 240)").unwrap();
+        assert_eq!(color, (63.0, 102.3, 240.0, 1.0));
+        let (_, color) = ycbcr_color("yuv(16, 128, 128, 0.5)").unwrap();
+        assert_eq!(color, (16.0, 128.0, 128.0, 0.5));
+
+        let color = parse_color("ycbcr(62.6 102.3 240)").unwrap().1;
+        assert_eq!(color.rgb(), (255.0, 0.0, 0.0, 1.0));
+        assert_eq!(color.to_string(), "ycbcr(62.6 102.3 240)");
+    }
+
+    #[test]
+    fn parse_xyz() {
+        let (_, color) = xyz_color("xyz(0.2 0.3 0.4)").unwrap();
+        assert_eq!(color, (ColorSpace::XyzD65, 0.2, 0.3, 0.4, 1.0));
+        let (_, color) = xyz_color("xyz-d50(20% 30% 40% / 0.5)").unwrap();
+        assert_eq!(color, (ColorSpace::XyzD50, 0.2, 0.3, 0.4, 0.5));
+        let (_, color) = xyz_color("xyz-d65(0.2 0.3 0.4)").unwrap();
+        assert_eq!(color.0, ColorSpace::XyzD65);
+
+        let shorthand = parse_color("xyz(0.2 0.3 0.4)").unwrap().1;
+        assert_eq!(shorthand, parse_color("color(xyz 0.2 0.3 0.4)").unwrap().1);
+        assert_eq!(shorthand.to_string(), "color(xyz-d65 0.2 0.3 0.4)");
+        assert!(
+            parse_color_as("xyz-d50(0.2 0.3 0.4)", ColorType::Color(ColorSpace::XyzD50)).is_ok()
+        );
+        assert!(parse_color_as("xyz(0.2 0.3 0.4)", ColorType::Color(ColorSpace::XyzD50)).is_err());
+    }
+
+    #[test]
+    fn parse_wavelength() {
+        let color = parse_color("550nm").unwrap().1;
+        assert_eq!(color, Color::from_wavelength(550.0));
+        assert_eq!(parse_color("700nm").unwrap().1.to_string(), "#FF0000");
+
+        assert!(parse_color("300nm").is_err());
+        assert!(parse_color("900nm").is_err());
+    }
 }
@@ -0,0 +1,63 @@
+/// Consonants and vowels that are hard to mishear, making 64 syllables of 6 bits
+/// each. Four syllables spell out a 24-bit colour.
+const CONSONANTS: [char; 16] = [
+    'b', 'd', 'f', 'g', 'h', 'j', 'k', 'l', 'm', 'n', 'p', 'r', 's', 't', 'v', 'z',
+];
+const VOWELS: [char; 4] = ['a', 'i', 'o', 'u'];
+
+/// Spells `0xRRGGBB` as two words of two syllables, e.g. `kato-rimu`.
+pub(crate) fn encode(value: u32) -> String {
+    let syllable = |shift: u32| {
+        let bits = (value >> shift) as usize & 0x3F;
+        [CONSONANTS[bits >> 2], VOWELS[bits & 3]]
+    };
+    let word = |shifts: [u32; 2]| -> String { shifts.into_iter().flat_map(syllable).collect() };
+
+    format!("{}-{}", word([18, 12]), word([6, 0]))
+}
+
+/// Reads a code written by `encode` back as `0xRRGGBB`. Case is ignored, as are
+/// hyphens and spaces, since codes read out loud are often written down loosely.
+pub(crate) fn decode(code: &str) -> Option<u32> {
+    let letters: Vec<char> = code
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if letters.len() != 8 {
+        return None;
+    }
+
+    letters.chunks(2).try_fold(0, |value, pair| {
+        let consonant = CONSONANTS.iter().position(|c| *c == pair[0])?;
+        let vowel = VOWELS.iter().position(|v| *v == pair[1])?;
+        Some(value << 6 | (consonant << 2 | vowel) as u32)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode_values() {
+        assert_eq!("baba-baba", encode(0x000000));
+        assert_eq!("zuzu-zuzu", encode(0xFFFFFF));
+        assert_eq!("kato-rimu", encode(0x636B63));
+        // The top byte isn't part of the colour
+        assert_eq!(encode(0x336699), encode(0xFF336699));
+    }
+
+    #[test]
+    fn decode_codes() {
+        for value in [0x000000, 0xFFFFFF, 0x336699, 0xFF8800, 0x123456] {
+            assert_eq!(Some(value), decode(&encode(value)));
+        }
+        assert_eq!(Some(0x636B63), decode("Kato Rimu"));
+        assert_eq!(Some(0x636B63), decode("ka-to-ri-mu"));
+
+        for code in ["", "kato", "kato-rimux", "kate-rimu", "cato-rimu"] {
+            assert_eq!(None, decode(code), "{}", code);
+        }
+    }
+}
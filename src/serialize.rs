@@ -0,0 +1,195 @@
+//! Serde support for `Color`, for keeping colours in config files.
+//!
+//! A colour serializes as the string it displays as, and deserializes from any string
+//! the parser accepts. The modules here pick another format for a single field, e.g.
+//! `#[serde(with = "color::serialize::hex")]`.
+
+use crate::colors::Color;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+struct ColorVisitor;
+
+impl<'de> Visitor<'de> for ColorVisitor {
+    type Value = Color;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a colour string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Color, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(ColorVisitor)
+    }
+}
+
+/// Serializes as a hex string like `#FF6347`.
+pub mod hex {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&color.hex_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        Color::deserialize(deserializer)
+    }
+}
+
+/// Serializes as an `rgb()` string like `rgb(255 99 71)`.
+pub mod rgb {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&color.rgb_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        Color::deserialize(deserializer)
+    }
+}
+
+/// Serializes as a struct of `red`, `green` and `blue` from 0-255, and `alpha` from 0-1.
+/// Alpha may be left out when deserializing.
+pub mod components {
+    use super::*;
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Components {
+        red: f32,
+        green: f32,
+        blue: f32,
+        #[serde(default = "opaque")]
+        alpha: f32,
+    }
+
+    fn opaque() -> f32 {
+        1.0
+    }
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        let (red, green, blue, alpha) = color.rgb();
+
+        Components {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let Components {
+            red,
+            green,
+            blue,
+            alpha,
+        } = Components::deserialize(deserializer)?;
+
+        Ok(Color::from_rgb(red, green, blue, alpha))
+    }
+}
+
+/// Serializes as an array of `[red, green, blue, alpha]`, with the same ranges as
+/// [`components`]. Alpha may be left out when deserializing.
+pub mod array {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        let (red, green, blue, alpha) = color.rgb();
+
+        [red, green, blue, alpha].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let values = Vec::<f32>::deserialize(deserializer)?;
+
+        match values[..] {
+            [red, green, blue] => Ok(Color::from_rgb(red, green, blue, 1.0)),
+            [red, green, blue, alpha] => Ok(Color::from_rgb(red, green, blue, alpha)),
+            _ => Err(de::Error::invalid_length(
+                values.len(),
+                &"3 or 4 components",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::colors::Color;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Theme {
+        background: Color,
+        #[serde(with = "crate::serialize::hex")]
+        hex: Color,
+        #[serde(with = "crate::serialize::rgb")]
+        rgb: Color,
+        #[serde(with = "crate::serialize::components")]
+        components: Color,
+        #[serde(with = "crate::serialize::array")]
+        array: Color,
+    }
+
+    #[test]
+    fn round_trip() {
+        let tomato: Color = "tomato".parse().unwrap();
+        let theme = Theme {
+            background: "hsl(0 100% 50%)".parse().unwrap(),
+            hex: tomato.clone(),
+            rgb: tomato.clone(),
+            components: tomato.clone(),
+            array: tomato.clone(),
+        };
+
+        let json = serde_json::to_string(&theme).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r##"{"background":"hsl(0 100 50)","hex":"#FF6347","rgb":"rgb(255 99 71)","##,
+                r#""components":{"red":255.0,"green":99.0,"blue":71.0,"alpha":1.0},"#,
+                r#""array":[255.0,99.0,71.0,1.0]}"#
+            )
+        );
+
+        let parsed: Theme = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.background.rgb(), theme.background.rgb());
+        for color in [parsed.hex, parsed.rgb, parsed.components, parsed.array] {
+            assert_eq!(color.rgb(), tomato.rgb());
+        }
+    }
+
+    #[test]
+    fn deserialize_any_parseable_string() {
+        let color: Color = serde_json::from_str(r#""Rgb( 1, 2, 3 )""#).unwrap();
+        assert_eq!(color.rgb(), (1.0, 2.0, 3.0, 1.0));
+
+        let color: Color = serde_json::from_str(r#""oklch(0.5 0.1 30 / 50%)""#).unwrap();
+        assert_eq!(color.rgb().3, 0.5);
+
+        let error = serde_json::from_str::<Color>(r#""not a colour""#).unwrap_err();
+        assert!(error.to_string().contains("expected a colour"), "{}", error);
+        assert!(serde_json::from_str::<Color>("[1, 2, 3]").is_err());
+
+        #[derive(Deserialize)]
+        struct Short(#[serde(with = "crate::serialize::array")] Color);
+        let Short(color) = serde_json::from_str("[1, 2, 3]").unwrap();
+        assert_eq!(color.rgb(), (1.0, 2.0, 3.0, 1.0));
+        assert!(serde_json::from_str::<Short>("[1, 2]").is_err());
+    }
+}
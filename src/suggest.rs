@@ -0,0 +1,83 @@
+/// The edit distance between two strings, counted in characters. This is the
+/// Levenshtein distance, except that swapping two adjacent characters counts as
+/// one edit, as it's such a common typo.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // distances[i][j] is the distance between the first i chars of a and j of b
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+/// Finds the candidate closest to `input`, if any is near enough to be a likely
+/// typo rather than a different word.
+pub(crate) fn closest<I, S>(input: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let max_distance = (input.chars().count() / 3).max(2);
+
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(input, candidate.as_ref()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_ref().to_string())
+}
+
+pub(crate) fn did_you_mean(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(suggestion) => format!(". Did you mean `{}`?", suggestion),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("red", "red"), 0);
+        assert_eq!(edit_distance("", "red"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("turqoise", "turquoise"), 1);
+        assert_eq!(edit_distance("oklhc", "oklch"), 1);
+    }
+
+    #[test]
+    fn closest_candidate() {
+        let names = ["red", "green", "blue"];
+
+        assert_eq!(closest("gren", names), Some("green".to_string()));
+        assert_eq!(closest("blu", names), Some("blue".to_string()));
+        assert_eq!(closest("purple", names), None);
+        assert_eq!(closest("grene", names), Some("green".to_string()));
+    }
+}